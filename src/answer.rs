@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::timing::StageTimer;
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<PuzzleAnswer>()
+        .add_systems(Update, (log_answers, show_answers));
+}
+
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+/// Sent by a day's `solve_a`/`solve_b` systems once an answer has been found.
+#[derive(Event, Clone, Debug)]
+pub struct PuzzleAnswer {
+    pub day: u8,
+    pub part: Part,
+    pub value: String,
+    /// How long the solver took to find the answer.
+    pub elapsed: Duration,
+}
+
+/// Sends [`PuzzleAnswer`]s, timed from the start of the current stage.
+#[derive(SystemParam)]
+pub struct Answers<'w> {
+    events: EventWriter<'w, PuzzleAnswer>,
+    timer: Res<'w, StageTimer>,
+}
+
+impl Answers<'_> {
+    pub fn send(&mut self, day: u8, part: Part, value: impl ToString) {
+        self.events.send(PuzzleAnswer {
+            day,
+            part,
+            value: value.to_string(),
+            elapsed: self.timer.elapsed(),
+        });
+    }
+}
+
+/// The node under each day's root that lists its answers.
+#[derive(Component, Debug)]
+pub struct AnswerPanel(pub u8);
+
+fn log_answers(mut answers: EventReader<PuzzleAnswer>) {
+    for answer in answers.read() {
+        info!(
+            "Day {} part {:?}: {} ({:.2?})",
+            answer.day, answer.part, answer.value, answer.elapsed
+        );
+    }
+}
+
+fn show_answers(
+    mut answers: EventReader<PuzzleAnswer>,
+    mut commands: Commands,
+    panels: Query<(Entity, &AnswerPanel)>,
+) {
+    for answer in answers.read() {
+        for (entity, panel) in &panels {
+            if panel.0 != answer.day {
+                continue;
+            }
+            commands.entity(entity).with_children(|p| {
+                p.spawn(Text::new(format!(
+                    "Part {:?}: {} ({:.2?})",
+                    answer.part, answer.value, answer.elapsed
+                )));
+            });
+        }
+    }
+}
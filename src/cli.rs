@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::answer::Part;
+
+/// Advent of Code 2024 solutions and visualisations. Opens the puzzle menu when run without a
+/// command.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Use the examples in `assets/sample` instead of the real puzzle inputs. Without it, the
+    /// window opens with whichever inputs it used last.
+    #[arg(long, global = true)]
+    pub sample: bool,
+    /// Solve every day in the background on startup, to fill in the menu's dashboard. Can also be
+    /// turned on in the settings file.
+    #[arg(long)]
+    pub solve_all: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve a single day without opening a window, printing the answers to stdout.
+    Run {
+        /// The puzzle day to solve.
+        #[arg(short, long)]
+        day: u8,
+        /// Only print the answer for this part.
+        #[arg(short, long)]
+        part: Option<Part>,
+        /// Solve this file instead of the day's input in `assets`. Loader settings are read from a
+        /// `.meta` file next to it, if there is one, or else are the ones the day's inputs use.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Solve each day repeatedly without opening a window, and report how long each stage took.
+    Bench {
+        /// Only benchmark this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+        /// How many times to solve each day.
+        #[arg(
+            short,
+            long,
+            default_value_t = 10,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        runs: u32,
+        /// Where to write the results, as CSV.
+        #[arg(short, long, default_value = "bench.csv")]
+        out: PathBuf,
+    },
+    /// Solve every input in a directory without opening a window, such as each team member's
+    /// input for each day, and print a table of their answers and timings. Inputs are named
+    /// `N-<name>.aoc`, where `N` is the day.
+    Batch {
+        /// The directory of inputs.
+        dir: PathBuf,
+        /// Only solve the inputs for this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+    },
+    /// Solve a day without opening a window, and draw its visualisation to an animated GIF or to
+    /// PNGs, one per frame.
+    Export {
+        /// The puzzle day to draw.
+        #[arg(short, long)]
+        day: u8,
+        /// Where to write the frames. Ending in `.gif` gives an animation, and `.png` a numbered
+        /// image per frame.
+        #[arg(short, long)]
+        out: PathBuf,
+        /// The most frames to draw. Longer visualisations skip steps to fit.
+        #[arg(
+            short,
+            long,
+            default_value_t = 100,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        frames: u32,
+        /// How many pixels wide each grid cell is drawn.
+        #[arg(
+            short,
+            long,
+            default_value_t = 4,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        scale: u32,
+    },
+    /// Solve a day without opening a window, and play its visualisation in the terminal with ANSI
+    /// colours. Works over SSH.
+    Show {
+        /// The puzzle day to draw.
+        #[arg(short, long)]
+        day: u8,
+        /// The most frames to draw. Longer visualisations skip steps to fit.
+        #[arg(
+            short,
+            long,
+            default_value_t = 100,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        frames: u32,
+        /// How many frames to draw each second.
+        #[arg(
+            long,
+            default_value_t = 10,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        fps: u32,
+    },
+    /// Download a day's puzzle input into `assets/input`, unless it is already there. The session
+    /// token is read from `AOC_SESSION`, or from a `.aoc-session` file.
+    Fetch {
+        /// The puzzle day to download.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Download from here instead of adventofcode.com, e.g. a local stand-in server. Also read
+        /// from `AOC_BASE_URL`.
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Send an answer to Advent of Code. Answers are kept in `.aoc-guesses.ron`, so the same one
+    /// is never sent twice.
+    Submit {
+        /// The puzzle day to answer.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The part to answer.
+        #[arg(short, long)]
+        part: Part,
+        /// The answer to send. Solves the day with its real input if not given.
+        answer: Option<String>,
+        /// Send to here instead of adventofcode.com, e.g. a local stand-in server. Also read from
+        /// `AOC_BASE_URL`.
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+}
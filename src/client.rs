@@ -0,0 +1,151 @@
+use std::fs;
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Where requests go unless `AOC_BASE_URL` or `--base-url` says otherwise.
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const YEAR: u16 = 2024;
+
+// Advent of Code asks automated tools to identify themselves.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Talks to Advent of Code, or a stand-in for it, as a logged in user.
+#[derive(Clone)]
+pub struct Client {
+    pub base_url: String,
+    /// The value of the `session` cookie from a logged in browser.
+    pub session: String,
+}
+
+impl Client {
+    /// Reads the session token from `AOC_SESSION`, or failing that from a `.aoc-session` file in
+    /// the working directory, and the base URL from `AOC_BASE_URL` if it is set.
+    pub fn from_env() -> Result<Self, NoSessionError> {
+        let session = match std::env::var("AOC_SESSION") {
+            Ok(session) => session,
+            Err(_) => fs::read_to_string(".aoc-session").map_err(|_| NoSessionError)?,
+        };
+        let session = session.trim();
+        if session.is_empty() {
+            return Err(NoSessionError);
+        }
+
+        Ok(Self {
+            base_url: std::env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into()),
+            session: session.into(),
+        })
+    }
+
+    /// The URL of a page for this year's puzzles, e.g. `day/1/input`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{YEAR}/{path}", self.base_url.trim_end_matches('/'))
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, ClientError> {
+        let url = self.url(path);
+        let response = self.request(ureq::get(&url)).call();
+        read(url, response)
+    }
+
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String, ClientError> {
+        let url = self.url(path);
+        let response = self.request(ureq::post(&url)).send_form(form);
+        read(url, response)
+    }
+
+    fn request(&self, request: ureq::Request) -> ureq::Request {
+        request
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+    }
+}
+
+fn read(url: String, response: Result<ureq::Response, ureq::Error>) -> Result<String, ClientError> {
+    match response {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(status, _)) => Err(ClientError::Status { url, status }),
+        Err(e) => Err(ClientError::Http(Box::new(e))),
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("No Advent of Code session token")]
+#[diagnostic(
+    code(aoc::client::session),
+    help(
+        "set AOC_SESSION to the `session` cookie from adventofcode.com, or save it in .aoc-session"
+    )
+)]
+pub struct NoSessionError;
+
+/// Possible errors when talking to Advent of Code.
+#[derive(Debug, Error, Diagnostic)]
+pub enum ClientError {
+    #[error("{url} responded with {status}")]
+    #[diagnostic(
+        code(aoc::client::status),
+        help("a 400 usually means the session token has expired, and a 404 that the puzzle isn't out yet")
+    )]
+    Status { url: String, status: u16 },
+
+    #[error("Could not reach Advent of Code: {0}")]
+    Http(Box<ureq::Error>),
+
+    #[error("Could not read the response: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A stand-in for Advent of Code, for testing against.
+#[cfg(test)]
+pub mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::Client;
+
+    /// Answers a single request with `body`, returning a client pointed at the server and a handle
+    /// that yields the request it received, headers and all.
+    pub fn serve_once(body: &'static str) -> (Client, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = Client {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            session: "secret".into(),
+        };
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request
+        });
+        (client, handle)
+    }
+}
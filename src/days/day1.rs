@@ -1,14 +1,18 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
+use miette::miette;
 
-use crate::{loading::PuzzleInputs, puzzle_input_asset::PuzzleInputAsset, AoCState};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::{line_span, Failures, InputError},
+    loading::PuzzleInputs,
+    puzzle_input_asset::PuzzleInputAsset,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<LocationDistances>()
-        .init_resource::<OrderedLocationLists>()
-        .add_systems(
-            OnEnter(AoCState::Day1),
-            (init, process, solve_a, solve_b).chain(),
-        );
+    app.init_day_resource::<Day1, LocationDistances>()
+        .init_day_resource::<Day1, OrderedLocationLists>()
+        .add_day::<Day1>();
 }
 
 #[derive(Default, Resource)]
@@ -23,48 +27,56 @@ struct LocationDistances {
     pub total: i32,
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day1;
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day1"),
-            Day1,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day1),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 1: Historian Hysteria"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Historian Hysteria";
+    type Input = PuzzleInputAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
 }
 
 fn process(
     mut processed: ResMut<OrderedLocationLists>,
     puzzle_assets: Res<Assets<PuzzleInputAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    if let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.one) {
+    if let Some(puzzle) = puzzle_inputs.get::<Day1>(&puzzle_assets) {
+        let Some(rows) = puzzle.rows::<i32>() else {
+            failures.report(Day1::DAY, miette!("Location IDs should be loaded as I32"));
+            return;
+        };
         let mut left_list: Vec<i32> = vec![];
         let mut right_list: Vec<i32> = vec![];
-        for row in &puzzle.rows {
-            left_list.push(row[0]);
-            right_list.push(row[1]);
+        for (i, row) in rows.iter().enumerate() {
+            let &[left, right] = row.as_slice() else {
+                failures.report(
+                    Day1::DAY,
+                    InputError::new(
+                        Day1::DAY,
+                        &puzzle.source,
+                        line_span(&puzzle.source, puzzle.line(0, i)),
+                        "Each line should have a location ID for both lists",
+                        format!("found {} location IDs", row.len()),
+                    ),
+                );
+                return;
+            };
+            left_list.push(left);
+            right_list.push(right);
         }
         left_list.sort();
         right_list.sort();
@@ -74,37 +86,23 @@ fn process(
 }
 
 fn solve_a(
-    mut commands: Commands,
-    day1: Single<Entity, With<Day1>>,
     locations: Res<OrderedLocationLists>,
     mut distances: ResMut<LocationDistances>,
+    mut answers: Answers,
 ) {
     for (left, right) in locations.left.iter().zip(locations.right.iter()) {
         distances.all.push(left.max(right) - left.min(right));
     }
     distances.total = distances.all.iter().sum();
-    dbg!(distances.total);
-    commands.entity(*day1).with_children(|p| {
-        p.spawn(Text::new(format!(
-            "Total distance between lists: {}",
-            distances.total
-        )));
-    });
+    answers.send(Day1::DAY, Part::A, distances.total);
 }
 
-fn solve_b(
-    mut commands: Commands,
-    day1: Single<Entity, With<Day1>>,
-    locations: Res<OrderedLocationLists>,
-    mut similarity: Local<i32>,
-) {
+fn solve_b(locations: Res<OrderedLocationLists>, mut answers: Answers) {
+    let mut similarity = 0;
     for loc in &locations.left {
-        *similarity += loc * locations.right.iter().filter(|&x| x == loc).count() as i32;
+        similarity += loc * locations.right.iter().filter(|&x| x == loc).count() as i32;
     }
-    dbg!(&similarity);
-    commands.entity(*day1).with_children(|p| {
-        p.spawn(Text::new(format!("List similarity: {}", *similarity)));
-    });
+    answers.send(Day1::DAY, Part::B, similarity);
 }
 
 // fn visualise(
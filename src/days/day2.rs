@@ -1,47 +1,50 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
+use miette::miette;
 
-use crate::{loading::PuzzleInputs, puzzle_input_asset::PuzzleInputAsset, AoCState};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::Failures,
+    loading::PuzzleInputs,
+    puzzle_input_asset::PuzzleInputAsset,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        OnEnter(AoCState::Day2),
-        (init, process, solve_a, solve_b).chain(),
-    );
+    app.add_day::<Day2>();
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day2;
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day2"),
-            Day2,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day2),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 2: Red-Nosed Reports"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+    type Input = PuzzleInputAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
 }
 
-// Currently no processing required.
-fn process() {}
+// The reports are used as they were loaded, as long as they were loaded as `i32`s.
+fn process(
+    puzzle_assets: Res<Assets<PuzzleInputAsset>>,
+    puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
+) {
+    let puzzle = puzzle_inputs.get::<Day2>(&puzzle_assets);
+    if puzzle.is_some_and(|puzzle| puzzle.rows::<i32>().is_none()) {
+        failures.report(Day2::DAY, miette!("Reports should be loaded as I32"));
+    }
+}
 
 fn is_safe(report: &[i32]) -> bool {
     let mut diffs: Vec<i32> = vec![];
@@ -63,27 +66,41 @@ fn is_safe(report: &[i32]) -> bool {
     true
 }
 
-fn solve_a(puzzle_assets: Res<Assets<PuzzleInputAsset>>, puzzle_inputs: Res<PuzzleInputs>) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.two) else {
+fn solve_a(
+    mut answers: Answers,
+    puzzle_assets: Res<Assets<PuzzleInputAsset>>,
+    puzzle_inputs: Res<PuzzleInputs>,
+) {
+    let Some(rows) = puzzle_inputs
+        .get::<Day2>(&puzzle_assets)
+        .and_then(|puzzle| puzzle.rows::<i32>())
+    else {
         return;
     };
     let mut safe_reports = 0;
 
-    for row in &puzzle.rows {
+    for row in rows {
         if is_safe(row) {
             safe_reports += 1;
         }
     }
-    dbg!(safe_reports);
+    answers.send(Day2::DAY, Part::A, safe_reports);
 }
 
-fn solve_b(puzzle_assets: Res<Assets<PuzzleInputAsset>>, puzzle_inputs: Res<PuzzleInputs>) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.two) else {
+fn solve_b(
+    mut answers: Answers,
+    puzzle_assets: Res<Assets<PuzzleInputAsset>>,
+    puzzle_inputs: Res<PuzzleInputs>,
+) {
+    let Some(rows) = puzzle_inputs
+        .get::<Day2>(&puzzle_assets)
+        .and_then(|puzzle| puzzle.rows::<i32>())
+    else {
         return;
     };
     let mut safe_reports = 0;
 
-    for row in &puzzle.rows {
+    for row in rows {
         if is_safe(row) {
             safe_reports += 1;
             continue;
@@ -97,5 +114,5 @@ fn solve_b(puzzle_assets: Res<Assets<PuzzleInputAsset>>, puzzle_inputs: Res<Puzz
             }
         }
     }
-    dbg!(safe_reports);
+    answers.send(Day2::DAY, Part::B, safe_reports);
 }
@@ -1,6 +1,6 @@
 use regex::Regex;
 
-use bevy::prelude::*;
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
 use miette::miette;
 use nom::{
     branch::alt,
@@ -12,49 +12,46 @@ use nom::{
     IResult,
 };
 
-use crate::{loading::PuzzleInputs, puzzle_input_lines_asset::PuzzleInputLinesAsset, AoCState};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::Failures,
+    loading::PuzzleInputs,
+    puzzle_input_lines_asset::PuzzleInputLinesAsset,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<ParsedStatements>().add_systems(
-        OnEnter(AoCState::Day3),
-        (init, process2, solve_a, solve_b).chain(),
-    );
+    app.init_day_resource::<Day3, ParsedStatements>()
+        .add_day::<Day3>();
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day3;
 
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Mull It Over";
+    type Input = PuzzleInputLinesAsset;
+
+    fn process() -> SystemConfigs {
+        process2.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+}
+
 #[derive(Resource, Default)]
 struct ParsedStatements {
+    /// Every `mul`, for part A.
     mul: Vec<(i32, i32)>,
-}
-
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day3"),
-            Day3,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day3),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 3: Mull It Over"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+    /// The `mul`s that a `don't()` hasn't turned off, for part B.
+    enabled_mul: Vec<(i32, i32)>,
 }
 
 // fn process(
@@ -62,7 +59,7 @@ fn init(mut commands: Commands) {
 //     puzzle_assets: Res<Assets<PuzzleInputLinesAsset>>,
 //     puzzle_inputs: Res<PuzzleInputs>,
 // ) {
-//     let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.three) else {
+//     let Some(puzzle) = puzzle_inputs.get::<Day3>(&puzzle_assets) else {
 //         return;
 //     };
 //     let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
@@ -91,25 +88,28 @@ fn init(mut commands: Commands) {
 // }
 
 fn process_line(line: &str) -> miette::Result<Vec<(i32, i32)>> {
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
     let mut dos: Vec<&str> = vec![];
     let (remainder, parsed) =
         many0(dont)(line).map_err(|e| miette!("don't parsing fail: {}", e))?;
-    dbg!(&parsed[0]);
 
     // The beginning of the string is considered a do() block because this is the default.
     // After this, we must check to see if mul() statements are "re-enabled" with a do()
     // statement.
-    dos.push(parsed[0]);
-
-    for chunk in &parsed[1..] {
-        let Ok((r, _)) = do_(chunk) else {
-            continue;
-        };
-        dos.push(r);
-    }
-    if let Ok((r, _)) = do_(remainder) {
-        dos.push(r);
+    match parsed.split_first() {
+        Some((first, rest)) => {
+            dos.push(first);
+            for chunk in rest {
+                let Ok((r, _)) = do_(chunk) else {
+                    continue;
+                };
+                dos.push(r);
+            }
+            if let Ok((r, _)) = do_(remainder) {
+                dos.push(r);
+            }
+        }
+        // Without a `don't()` nothing was split off, and every statement is enabled.
+        None => dos.push(remainder),
     }
 
     // At this point, we should have all statements following `do()` AND the initial statements
@@ -117,39 +117,47 @@ fn process_line(line: &str) -> miette::Result<Vec<(i32, i32)>> {
 
     let mut parsed_statements: Vec<(i32, i32)> = vec![];
     for chunk in &dos {
-        let (_, p) = many0(mul)(chunk).map_err(|e| miette!("do parsing fail: {}", e))?;
-
-        parsed_statements.append(
-            &mut p
-                .iter()
-                .filter(|x| **x != "mul(")
-                .filter_map(|x| {
-                    re.captures(x).map(|caps| {
-                        let lhs: i32 = caps[1].parse().unwrap();
-                        let rhs: i32 = caps[2].parse().unwrap();
-                        (lhs, rhs)
-                    })
-                })
-                .collect::<Vec<(i32, i32)>>(),
-        );
+        parsed_statements.append(&mut process_muls(chunk)?);
     }
 
     Ok(parsed_statements)
 }
 
+// Every well-formed `mul` in `input`, whether or not it is enabled.
+fn process_muls(input: &str) -> miette::Result<Vec<(i32, i32)>> {
+    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+    let (_, p) = many0(mul)(input).map_err(|e| miette!("do parsing fail: {}", e))?;
+    Ok(p.iter()
+        .filter(|x| **x != "mul(")
+        .filter_map(|x| {
+            re.captures(x).map(|caps| {
+                let lhs: i32 = caps[1].parse().unwrap();
+                let rhs: i32 = caps[2].parse().unwrap();
+                (lhs, rhs)
+            })
+        })
+        .collect())
+}
+
 fn process2(
     mut parsed_statements: ResMut<ParsedStatements>,
     puzzle_assets: Res<Assets<PuzzleInputLinesAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.three) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day3>(&puzzle_assets) else {
         return;
     };
-    let Ok(mut statements) = process_line(&puzzle.rows.concat()) else {
-        dbg!("EARLY EXIT");
-        return;
-    };
-    parsed_statements.mul.append(&mut statements);
+    let input = puzzle.rows.concat();
+    let parsed =
+        process_muls(&input).and_then(|all| process_line(&input).map(|enabled| (all, enabled)));
+    match parsed {
+        Ok((all, enabled)) => {
+            parsed_statements.mul = all;
+            parsed_statements.enabled_mul = enabled;
+        }
+        Err(e) => failures.report(Day3::DAY, e),
+    }
 }
 
 fn dont(input: &str) -> IResult<&str, &str> {
@@ -173,29 +181,40 @@ fn mul(input: &str) -> IResult<&str, &str> {
     ))(remainder)
 }
 
-fn solve_a(parsed_statements: Res<ParsedStatements>) {
+fn solve_a(mut answers: Answers, parsed_statements: Res<ParsedStatements>) {
     let mut total = 0;
     for (lhs, rhs) in &parsed_statements.mul {
         total += lhs * rhs;
     }
-    dbg!(total);
+    answers.send(Day3::DAY, Part::A, total);
 }
 
-fn solve_b(parsed_statements: Res<ParsedStatements>) {
+fn solve_b(mut answers: Answers, parsed_statements: Res<ParsedStatements>) {
     let mut total = 0;
-    for (lhs, rhs) in &parsed_statements.mul {
+    for (lhs, rhs) in &parsed_statements.enabled_mul {
         total += lhs * rhs;
     }
-    dbg!(total);
+    answers.send(Day3::DAY, Part::B, total);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_a() -> miette::Result<()> {
+        // The `don't()`s in the sample only matter for part B.
+        let input = include_str!("../../assets/sample/3.aoc");
+        let actual = process_muls(input)?
+            .iter()
+            .fold(0, |acc, (a, b)| acc + a * b);
+        assert_eq!(actual, 161);
+        Ok(())
+    }
+
     #[test]
     fn test_process2() -> miette::Result<()> {
-        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let input = include_str!("../../assets/sample/3.aoc");
         let expected = process_line(input)?
             .iter()
             .fold(0, |acc, (a, b)| acc + a * b);
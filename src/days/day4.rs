@@ -1,45 +1,43 @@
-use crate::{loading::PuzzleInputs, puzzle_input_lines_asset::PuzzleInputLinesAsset, AoCState};
-use bevy::prelude::*;
-use miette::miette;
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    export::{Cell, Frame},
+    grid::Grid,
+    loading::PuzzleInputs,
+    puzzle_input_grid_asset::PuzzleInputGridAsset,
+};
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<WordSearch>()
-        .init_resource::<WordSearchGrid>()
-        .add_systems(
-            OnEnter(AoCState::Day4),
-            (init, process, process2, solve_a, solve_b, vis).chain(),
-        );
+    app.init_day_resource::<Day4, WordSearch>()
+        .init_day_resource::<Day4, WordSearchGrid>()
+        .add_day::<Day4>()
+        .add_export::<Day4, _>(draw_frames);
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day4;
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day4"),
-            Day4,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day4),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 4: Ceres Search"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Ceres Search";
+    type Input = PuzzleInputGridAsset;
+
+    fn process() -> SystemConfigs {
+        (process, process2).chain().into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
 }
 
 #[derive(Resource, Default, Debug)]
@@ -57,7 +55,7 @@ struct Character {
 
 #[derive(Resource, Default, Debug)]
 struct WordSearchGrid {
-    pub characters: Vec<Vec<Character>>,
+    pub characters: Grid<Character>,
 }
 
 fn get_diagonals(rows: &Vec<String>) -> Vec<String> {
@@ -76,13 +74,11 @@ fn get_diagonals(rows: &Vec<String>) -> Vec<String> {
     // Get the rest of the diagonals
     for i in 1..rows.len() {
         let mut diag = String::new();
-        let mut col = i;
-        for row in rows {
+        for (col, row) in (i..).zip(rows) {
             let chars: Vec<char> = row.chars().collect();
             if col < row.len() {
                 diag.push(chars[col]);
             }
-            col += 1;
         }
         diagonals.push(diag);
     }
@@ -92,7 +88,8 @@ fn get_diagonals(rows: &Vec<String>) -> Vec<String> {
 
 fn get_verticals(rows: &Vec<String>) -> Vec<String> {
     let mut verticals: Vec<String> = vec![];
-    for (col, _) in rows.iter().enumerate() {
+    let width = rows.first().map_or(0, String::len);
+    for col in 0..width {
         let mut vert = String::new();
 
         for row in rows {
@@ -112,24 +109,23 @@ fn get_verticals(rows: &Vec<String>) -> Vec<String> {
 // 3a. create other diagonal, process back/forward
 // 4. create verticals, process backwards and forwards
 fn process(
-    puzzle_assets: Res<Assets<PuzzleInputLinesAsset>>,
+    puzzle_assets: Res<Assets<PuzzleInputGridAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
     mut word_search: ResMut<WordSearch>,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.four) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day4>(&puzzle_assets) else {
         return;
     };
 
-    for row in &puzzle.rows {
+    let rows: Vec<String> = puzzle.0.rows().map(|row| row.iter().collect()).collect();
+    for row in &rows {
         word_search.horizontal.push(row.into());
     }
 
-    word_search
-        .vertical
-        .append(&mut get_verticals(&puzzle.rows));
+    word_search.vertical.append(&mut get_verticals(&rows));
 
-    let mut diagonals = get_diagonals(&puzzle.rows);
-    let mut reversed = puzzle.rows.clone();
+    let mut diagonals = get_diagonals(&rows);
+    let mut reversed = rows;
     reversed.reverse();
     let mut r_diags = get_diagonals(&reversed);
 
@@ -137,51 +133,45 @@ fn process(
     word_search.diagonal.append(&mut r_diags);
 }
 
-fn make_grid(input: &Vec<String>) -> Vec<Vec<Character>> {
-    let mut grid: Vec<Vec<Character>> = vec![];
-
-    for row in input {
-        let mut r: Vec<Character> = vec![];
-        for character in row.chars() {
-            r.push(Character {
-                letter: character,
-                is_match: false,
-            });
-        }
-        grid.push(r);
-    }
-
-    grid
+fn make_grid(input: &Grid<char>) -> Grid<Character> {
+    input.map(|&letter| Character {
+        letter,
+        is_match: false,
+    })
 }
 
+// The corners of the X-MAS centred on `centre`, if there is one:
 // M S       M M      S S      S M
 //  A    or   A   or   A   or   A
 // M S       S S      M M      S M
-fn is_x(grid: &[Vec<Character>]) -> bool {
-    let lhs = [grid[0][0].letter, grid[1][1].letter, grid[2][2].letter];
-    let rhs = [grid[0][2].letter, grid[1][1].letter, grid[2][0].letter];
-    (lhs == ['M', 'A', 'S'] || lhs == ['S', 'A', 'M'])
-        && (rhs == ['M', 'A', 'S'] || rhs == ['S', 'A', 'M'])
-}
-
-fn grid_slice(grid: &[Vec<Character>], index: (usize, usize)) -> Vec<Vec<Character>> {
-    vec![
-        grid[index.0][index.1..index.1 + 3].to_vec(),
-        grid[index.0 + 1][index.1..index.1 + 3].to_vec(),
-        grid[index.0 + 2][index.1..index.1 + 3].to_vec(),
-    ]
+fn x_corners(grid: &Grid<Character>, centre: IVec2) -> Option<[IVec2; 4]> {
+    if grid.get(centre)?.letter != 'A' {
+        return None;
+    }
+    // Clockwise from the top right, so opposite corners are two apart. There are fewer at an edge.
+    let corners: Vec<(IVec2, char)> = grid
+        .neighbours8(centre)
+        .filter(|(pos, _)| (*pos - centre).abs() == IVec2::ONE)
+        .map(|(pos, c)| (pos, c.letter))
+        .collect();
+    let &[a, b, c, d] = corners.as_slice() else {
+        return None;
+    };
+    let is_mas =
+        |(_, x): (IVec2, char), (_, y): (IVec2, char)| matches!((x, y), ('M', 'S') | ('S', 'M'));
+    (is_mas(a, c) && is_mas(b, d)).then_some([a.0, b.0, c.0, d.0])
 }
 
 fn process2(
-    puzzle_assets: Res<Assets<PuzzleInputLinesAsset>>,
+    puzzle_assets: Res<Assets<PuzzleInputGridAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
     mut grid: ResMut<WordSearchGrid>,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.four) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day4>(&puzzle_assets) else {
         return;
     };
 
-    grid.characters = make_grid(&puzzle.rows);
+    grid.characters = make_grid(&puzzle.0);
 }
 
 fn count_xmas(input: Vec<String>) -> miette::Result<usize> {
@@ -198,25 +188,21 @@ fn count_xmas(input: Vec<String>) -> miette::Result<usize> {
     Ok(count)
 }
 
-fn count_x(input: &mut [Vec<Character>]) -> miette::Result<usize> {
-    let mut count = 0;
-    for i in 0..input.len() - 2 {
-        for j in 0..input[i].len() - 2 {
-            let check_grid = grid_slice(input, (i, j));
-            if is_x(&check_grid) {
-                input[i][j].is_match = true;
-                input[i][j + 2].is_match = true;
-                input[i + 1][j + 1].is_match = true;
-                input[i + 2][j].is_match = true;
-                input[i + 2][j + 2].is_match = true;
-                count += 1;
-            }
+fn count_x(input: &mut Grid<Character>) -> miette::Result<usize> {
+    let xs: Vec<(IVec2, [IVec2; 4])> = input
+        .iter()
+        .filter_map(|(pos, _)| Some((pos, x_corners(input, pos)?)))
+        .collect();
+    for (centre, corners) in &xs {
+        input[*centre].is_match = true;
+        for corner in corners {
+            input[*corner].is_match = true;
         }
     }
-    Ok(count)
+    Ok(xs.len())
 }
 
-fn solve_a(word_search: Res<WordSearch>) {
+fn solve_a(mut answers: Answers, word_search: Res<WordSearch>) {
     let Ok(horizontal) = count_xmas(word_search.horizontal.clone()) else {
         return;
     };
@@ -227,17 +213,15 @@ fn solve_a(word_search: Res<WordSearch>) {
         return;
     };
 
-    dbg!(horizontal + vertical + diagonal);
+    let total = horizontal + vertical + diagonal;
+    answers.send(Day4::DAY, Part::A, total);
 }
 
-fn solve_b(mut grid: ResMut<WordSearchGrid>) {
+fn solve_b(mut answers: Answers, mut grid: ResMut<WordSearchGrid>) {
     let Ok(x) = count_x(&mut grid.characters) else {
         return;
     };
-    // let matched_chars = grid.characters.iter().filter(|x| x.is_match).count();
-
-    dbg!(x);
-    // dbg!(matched_chars);
+    answers.send(Day4::DAY, Part::B, x);
 }
 
 #[derive(Component)]
@@ -249,7 +233,7 @@ fn vis(
     day4: Single<Entity, With<Day4>>,
     grid: Res<WordSearchGrid>,
 ) {
-    for row in &grid.characters {
+    for row in grid.characters.rows() {
         let ui_row = commands
             .spawn(Node {
                 align_items: AlignItems::Center,
@@ -274,7 +258,7 @@ fn vis(
                         margin: UiRect::all(Val::Px(3.)),
                         ..default()
                     },
-                    StateScoped(AoCState::Day4),
+                    StateScoped(Day4::state()),
                 ))
                 .with_children(|p| {
                     let letter = if col.is_match { "." } else { "🎄" };
@@ -292,9 +276,27 @@ fn vis(
     }
 }
 
+// The letters of every X-MAS found in part B lit up, with the rest greyed out.
+fn frame(grid: &Grid<Character>) -> Frame {
+    Frame {
+        cells: grid.map(|c| match (c.is_match, c.letter) {
+            (true, _) => Cell::Match,
+            (false, 'X' | 'M' | 'A' | 'S') => Cell::Wall,
+            (false, _) => Cell::Empty,
+        }),
+        text: Some(grid.map(|c| c.letter)),
+    }
+}
+
+fn draw_frames(_frames: In<usize>, grid: Res<WordSearchGrid>) -> Vec<Frame> {
+    vec![frame(&grid.characters)]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::terminal;
+    use miette::miette;
 
     fn find_all(input: Vec<&str>) -> miette::Result<usize> {
         let strings: Vec<String> = input.clone().into_iter().map(String::from).collect();
@@ -313,18 +315,7 @@ mod tests {
 
     #[test]
     fn test_part1() -> miette::Result<()> {
-        let input = vec![
-            "MMMSXXMASM",
-            "MSAMXMSMSA",
-            "AMXSXMAAMM",
-            "MSAMASMSMX",
-            "XMASAMXAMM",
-            "XXAMMXXAMA",
-            "SMSMSASXSS",
-            "SAXAMASAAA",
-            "MAMMMXMMMM",
-            "MXMXAXMASX",
-        ];
+        let input: Vec<&str> = include_str!("../../assets/sample/4.aoc").lines().collect();
         let expected = 18;
         let actual = find_all(input).map_err(|e| miette!("badness happened: {}", e))?;
         assert_eq!(expected, actual);
@@ -333,35 +324,36 @@ mod tests {
 
     #[test]
     fn test_part2() -> miette::Result<()> {
-        let input = vec![
-            "MMMSXXMASM",
-            "MSAMXMSMSA",
-            "AMXSXMAAMM",
-            "MSAMASMSMX",
-            "XMASAMXAMM",
-            "XXAMMXXAMA",
-            "SMSMSASXSS",
-            "SAXAMASAAA",
-            "MAMMMXMMMM",
-            "MXMXAXMASX",
-        ];
-        let mut grid = make_grid(&input.into_iter().map(String::from).collect());
+        let input: Grid<char> = include_str!("../../assets/sample/4.aoc").parse()?;
+        let mut grid = make_grid(&input);
         let expected = 9;
         let actual = count_x(&mut grid)?;
-        let mut vis = vec![];
-        for row in grid {
-            let mut r = String::new();
-            for col in row {
-                if col.is_match {
-                    r.push(col.letter);
-                } else {
-                    r.push('.');
-                }
-            }
-            vis.push(r);
-        }
-        dbg!(vis);
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_frame() -> miette::Result<()> {
+        let input: Grid<char> = include_str!("../../assets/sample/4.aoc").parse()?;
+        let mut grid = make_grid(&input);
+        count_x(&mut grid)?;
+
+        // The letters are drawn over the cells, so leave them out to see which ones lit up.
+        let mut frame = frame(&grid);
+        assert_eq!(frame.text.take(), Some(input));
+        let expected = "\
+#*#*######
+##*##****#
+#*#*#***##
+##*#*****#
+#*#*#*####
+##########
+*#*#*#*#*#
+#*#*#*#*##
+*#*#*#*#*#
+##########
+";
+        assert_eq!(terminal::render(&frame, false), expected);
+        Ok(())
+    }
 }
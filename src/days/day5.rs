@@ -1,53 +1,60 @@
 use std::str::FromStr;
 
-use crate::{loading::PuzzleInputs, puzzle_input_lines_asset::PuzzleInputLinesAsset, AoCState};
-use bevy::prelude::*;
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::{line_span, Failures, InputError},
+    loading::PuzzleInputs,
+    puzzle_input_asset::{Delimiter, PuzzleInputAsset, PuzzleInputSettings},
+};
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
 use miette::miette;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<PrintQueue>().add_systems(
-        OnEnter(AoCState::Day5),
-        (init, process, solve_a, solve_b, vis).chain(),
-    );
+    app.init_day_resource::<Day5, PrintQueue>()
+        .add_day::<Day5>();
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day5;
 
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Print Queue";
+    type Input = PuzzleInputAsset;
+
+    fn load_input_without_meta(
+        asset_server: &AssetServer,
+        path: String,
+    ) -> Handle<PuzzleInputAsset> {
+        asset_server.load_with_settings(path, |settings: &mut PuzzleInputSettings| {
+            *settings = input_settings();
+        })
+    }
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
+}
+
 #[derive(Resource, Debug, Default)]
 struct PrintQueue {
     pub rules: Vec<Rule>,
     pub updates: Vec<Vec<i32>>,
 }
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day5"),
-            Day5,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day5),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 5: Print Queue"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
-}
-
 #[derive(Copy, Clone, Default, Debug)]
 struct Rule {
     pub before: i32,
@@ -72,31 +79,56 @@ impl FromStr for Rule {
     }
 }
 
-fn get_rules(input: &[&str]) -> miette::Result<Vec<Rule>> {
-    input.iter().map(|&row| row.parse()).collect()
-}
-
 fn process(
     mut queue: ResMut<PrintQueue>,
-    puzzle_assets: Res<Assets<PuzzleInputLinesAsset>>,
+    puzzle_assets: Res<Assets<PuzzleInputAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.five) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day5>(&puzzle_assets) else {
         return;
     };
 
-    let Some(divider) = puzzle.rows.iter().position(|x| x.is_empty()) else {
-        return;
-    };
+    match parse_print_queue(puzzle) {
+        Ok(parsed) => *queue = parsed,
+        Err(e) => failures.report(Day5::DAY, e),
+    }
+}
+
+// The same settings as `5.aoc.meta`, for inputs without a `.meta` file of their own.
+fn input_settings() -> PuzzleInputSettings {
+    PuzzleInputSettings {
+        delimiter: Delimiter::Custom("|,".into()),
+        sections: true,
+        ..default()
+    }
+}
 
-    let (rule_section, update_section) = puzzle.rows.split_at(divider);
+// The rules and updates are separate sections of the input, which `5.aoc.meta` has the loader
+// split on both `|` and `,`.
+fn parse_print_queue(puzzle: &PuzzleInputAsset) -> miette::Result<PrintQueue> {
+    let Some([rules, updates]) = puzzle.sections::<i32>() else {
+        return Err(miette!(
+            "Expected the rules and the updates as two sections of I32s, split by a blank line"
+        ));
+    };
 
-    let r: Vec<&str> = rule_section.iter().map(String::as_ref).collect();
-    queue.rules = get_rules(&r).unwrap();
-    queue.updates = update_section[1..]
-        .iter()
-        .map(|l| l.split(",").map(|n| n.parse::<i32>().unwrap()).collect())
-        .collect();
+    let mut queue = PrintQueue::default();
+    for (i, rule) in rules.iter().enumerate() {
+        let &[before, after] = rule.as_slice() else {
+            return Err(InputError::new(
+                Day5::DAY,
+                &puzzle.source,
+                line_span(&puzzle.source, puzzle.line(0, i)),
+                "Malformed page ordering rule",
+                format!("expected 2 page numbers, found {}", rule.len()),
+            )
+            .into());
+        };
+        queue.rules.push(Rule { before, after });
+    }
+    queue.updates = updates.clone();
+    Ok(queue)
 }
 
 fn update_is_valid(rules: &[Rule], update: &[i32]) -> bool {
@@ -138,32 +170,31 @@ fn fix_update(rules: &[Rule], update: &[i32]) -> miette::Result<Vec<i32>> {
     Ok(fixed)
 }
 
-fn solve_a(queue: Res<PrintQueue>) {
+fn solve_a(mut answers: Answers, queue: Res<PrintQueue>) {
     let mut total = 0;
     for update in &queue.updates {
         if update_is_valid(&queue.rules, update) {
             total += update[(update.len() as f32 / 2.).floor() as usize];
         }
     }
-    dbg!(total);
+    answers.send(Day5::DAY, Part::A, total);
 }
 
-fn solve_b(queue: Res<PrintQueue>) {
+fn solve_b(mut answers: Answers, queue: Res<PrintQueue>, mut failures: Failures) {
     let mut total = 0;
-    let mut fixed_updates: Vec<Vec<i32>> = vec![];
     for update in &queue.updates {
-        if !update_is_valid(&queue.rules, update) {
-            let fixed = fix_update(&queue.rules, update).unwrap();
-            fixed_updates.push(fixed.clone());
-            total += fixed[(fixed.len() as f32 / 2.).floor() as usize];
+        if update_is_valid(&queue.rules, update) {
+            continue;
         }
-    }
-    for update in fixed_updates {
-        if !update_is_valid(&queue.rules, &update) {
-            info!("INVALID: {:?}", update);
+        match fix_update(&queue.rules, update) {
+            Ok(fixed) => total += fixed[(fixed.len() as f32 / 2.).floor() as usize],
+            Err(e) => {
+                failures.report(Day5::DAY, e);
+                return;
+            }
         }
     }
-    dbg!(total);
+    answers.send(Day5::DAY, Part::B, total);
 }
 
 const fn vis(
@@ -177,36 +208,13 @@ const fn vis(
 mod tests {
     use super::*;
 
+    fn get_rules(input: &[&str]) -> miette::Result<Vec<Rule>> {
+        input.iter().map(|&row| row.parse()).collect()
+    }
+
     #[test]
     fn test_a() -> miette::Result<()> {
-        let input = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
+        let input = include_str!("../../assets/sample/5.aoc");
         let (rules, updates) = input.split_once("\n\n").unwrap();
         let r = get_rules(&rules.lines().collect::<Vec<&str>>())?;
         let u: Vec<Vec<i32>> = updates
@@ -233,34 +241,7 @@ mod tests {
 
     #[test]
     fn test_b() -> miette::Result<()> {
-        let input = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
+        let input = include_str!("../../assets/sample/5.aoc");
         let (rules, updates) = input.split_once("\n\n").unwrap();
         let r = get_rules(&rules.lines().collect::<Vec<&str>>())?;
         let u: Vec<Vec<i32>> = updates
@@ -284,7 +265,6 @@ mod tests {
         }
         let mut actual = 0;
         for update in fixed.iter() {
-            dbg!(&update);
             actual += update[(update.len() as f32 / 2.).floor() as usize];
         }
 
@@ -292,4 +272,13 @@ mod tests {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_rule() {
+        let input = "47|53\n97\n\n75,47,61\n";
+        let puzzle = PuzzleInputAsset::parse("5.aoc", input.into(), &input_settings()).unwrap();
+        let error = parse_print_queue(&puzzle).unwrap_err();
+        let label = error.labels().unwrap().next().unwrap();
+        assert_eq!((label.offset(), label.len()), (6, 2));
+    }
 }
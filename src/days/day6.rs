@@ -1,21 +1,59 @@
 #![allow(dead_code)]
 
-use crate::{loading::PuzzleInputs, puzzle_input_string_asset::PuzzleInputStringAsset, AoCState};
-use bevy::{prelude::*, utils::HashMap};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    export::{self, Cell, Frame},
+    failure::Failures,
+    grid::Grid,
+    loading::PuzzleInputs,
+    puzzle_input_grid_asset::PuzzleInputGridAsset,
+    tasks::Solvers,
+    timeline::{Replay, Timeline},
+};
+use bevy::{
+    color::palettes::css::{DIM_GRAY, GOLD, ORANGE_RED},
+    ecs::schedule::SystemConfigs,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use miette::miette;
+use serde::{Deserialize, Serialize};
 // use chumsky::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<AreaMap>().add_systems(
-        OnEnter(AoCState::Day6),
-        (init, process, solve_a, solve_b, vis).chain(),
-    );
+    app.init_day_resource::<Day6, AreaMap>()
+        .add_day::<Day6>()
+        .add_timeline::<Day6, Patrol, _>(draw_patrol)
+        .add_export::<Day6, _>(draw_frames);
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day6;
 
-#[derive(Clone, Debug, Default)]
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Guard Gallivant";
+    type Input = PuzzleInputGridAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[allow(dead_code)]
 enum GuardFacing {
     #[default]
@@ -28,9 +66,11 @@ enum GuardFacing {
 #[derive(Resource, Default, Debug)]
 struct AreaMap {
     pub obstacles: HashMap<IVec2, bool>,
+    pub bounds: IRect,
+    pub start: IVec2,
 }
 
-#[derive(Component, Clone, Default, Debug)]
+#[derive(Component, Clone, Default, Debug, Serialize, Deserialize)]
 struct Guard {
     pub facing: GuardFacing,
     pub pos: IVec2,
@@ -74,7 +114,7 @@ impl Guard {
         }
     }
 
-    fn turn_right(&mut self) {
+    const fn turn_right(&mut self) {
         self.facing = match self.facing {
             GuardFacing::East => GuardFacing::South,
             GuardFacing::South => GuardFacing::West,
@@ -84,6 +124,76 @@ impl Guard {
     }
 }
 
+/// The guard's walk from part A, as the state of a [`Timeline`]. The map is included so that a
+/// saved walk can be drawn without the puzzle input.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Patrol {
+    obstacles: Vec<IVec2>,
+    bounds: IRect,
+    guard: Guard,
+}
+
+/// Where the guard is, and which way they face, after a step.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PatrolStep {
+    pos: IVec2,
+    facing: GuardFacing,
+}
+
+impl Replay for Patrol {
+    type Step = PatrolStep;
+
+    // Like `Guard::advance`, a position only counts as visited once the guard moves on from it.
+    fn apply(&mut self, step: &PatrolStep) {
+        if step.pos != self.guard.pos && !self.guard.visited.contains(&self.guard.pos) {
+            self.guard.visited.push(self.guard.pos);
+        }
+        self.guard.pos = step.pos;
+        self.guard.facing = step.facing.clone();
+    }
+}
+
+impl Patrol {
+    /// Walks the guard out of the area as part A does, recording every step.
+    fn record(area_map: &AreaMap) -> Timeline<Self> {
+        let start = Self {
+            obstacles: area_map.obstacles.keys().copied().collect(),
+            bounds: area_map.bounds,
+            guard: Guard {
+                pos: area_map.start,
+                facing: GuardFacing::North,
+                ..default()
+            },
+        };
+
+        let mut guard = start.guard.clone();
+        let mut steps = vec![];
+        while area_map.bounds.contains(guard.pos) {
+            guard.advance(&area_map.obstacles);
+            steps.push(PatrolStep {
+                pos: guard.pos,
+                facing: guard.facing.clone(),
+            });
+        }
+        Timeline::new(start, steps)
+    }
+
+    /// Draws the area for the export command, in the same colours as `draw_patrol`.
+    fn frame(&self) -> Frame {
+        let mut frame = export::blank(self.bounds);
+        for pos in &self.obstacles {
+            frame[*pos] = Cell::Wall;
+        }
+        for pos in &self.guard.visited {
+            frame[*pos] = Cell::Visited;
+        }
+        if let Some(cell) = frame.cells.get_mut(self.guard.pos) {
+            *cell = Cell::Marker;
+        }
+        frame
+    }
+}
+
 // Every character is EITHER:
 //   - empty space ('.')
 //   - an obstacle ('#')
@@ -96,120 +206,142 @@ enum Position {
     Guard,
 }
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day6"),
-            Day6,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day6),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 6: Guard Gallivant"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
-}
-
-fn find_guard(input: &[&str]) -> miette::Result<Guard> {
-    for (y, row) in input.iter().enumerate() {
-        if let Some(x) = row.find("^") {
-            return Ok(Guard {
-                facing: GuardFacing::North,
-                pos: IVec2::new(x as i32, y as i32),
-                ..default()
-            });
-        }
-    }
-
-    Err(miette!("NO GUARD!"))
+fn find_guard(input: &Grid<char>) -> miette::Result<Guard> {
+    let pos = input
+        .find(&'^')
+        .ok_or_else(|| miette!("No guard (`^`) found on the map"))?;
+    Ok(Guard {
+        facing: GuardFacing::North,
+        pos,
+        ..default()
+    })
 }
 
-fn find_obstacles(input: &[&str]) -> miette::Result<HashMap<IVec2, bool>> {
-    let mut obstacles: HashMap<IVec2, bool> = [].into();
-    for (y, row) in input.iter().enumerate() {
-        for (x, _) in row.match_indices("#") {
-            obstacles.insert((x as i32, y as i32).into(), true);
-        }
-    }
-
-    Ok(obstacles)
+fn find_obstacles(input: &Grid<char>) -> miette::Result<HashMap<IVec2, bool>> {
+    Ok(input.find_all(&'#').map(|pos| (pos, true)).collect())
 }
 
 fn process(
     mut area_map: ResMut<AreaMap>,
     mut commands: Commands,
-    puzzle_assets: Res<Assets<PuzzleInputStringAsset>>,
+    puzzle_assets: Res<Assets<PuzzleInputGridAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.six) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day6>(&puzzle_assets) else {
         return;
     };
 
-    let input: Vec<&str> = puzzle.0.lines().collect();
-    commands.spawn(find_guard(&input).unwrap());
-    area_map.obstacles = find_obstacles(&input).unwrap();
+    let input = &puzzle.0;
+    let guard = match find_guard(input) {
+        Ok(guard) => guard,
+        Err(e) => {
+            failures.report(Day6::DAY, e);
+            return;
+        }
+    };
+    area_map.start = guard.pos;
+    area_map.bounds = input.bounds();
+    area_map.obstacles = find_obstacles(input).unwrap();
+    commands.spawn((guard, StateScoped(Day6::state())));
 }
 
-fn solve_a(area_map: ResMut<AreaMap>, mut guard: Single<&mut Guard>) {
-    let bounds = IRect {
-        min: IVec2::ZERO,
-        max: IVec2::new(129, 129),
-    };
-    while bounds.contains(guard.pos) {
+fn solve_a(mut answers: Answers, area_map: ResMut<AreaMap>, mut guard: Single<&mut Guard>) {
+    while area_map.bounds.contains(guard.pos) {
         guard.advance(&area_map.obstacles);
     }
-    dbg!(&guard.visited.len());
+    answers.send(Day6::DAY, Part::A, guard.visited.len());
 }
 
-fn solve_b(area_map: ResMut<AreaMap>, guard: Single<&Guard>) {
+// Walks the whole route again for every position an obstacle could be added at, so this part is
+// solved in the background.
+fn solve_b(mut solvers: Solvers, area_map: Res<AreaMap>, guard: Single<&Guard>) {
     let circuit_testing_obstacles = area_map.obstacles.clone();
     let circuit_testing_guard = Guard {
-        pos: IVec2::new(80, 58),
+        pos: area_map.start,
         facing: GuardFacing::North,
         ..default()
     };
+    let bounds = area_map.bounds;
+    let candidates = guard.visited.clone();
 
-    let bounds = IRect {
-        min: IVec2::ZERO,
-        max: IVec2::new(129, 129),
-    };
-    let mut loops = 0;
-    for candidate in guard.visited.iter() {
-        let mut test_guard = circuit_testing_guard.clone();
-        let mut test_obstacles = circuit_testing_obstacles.clone();
-        test_obstacles.insert(*candidate, true);
-        let mut iterations: i64 = 0;
-        while bounds.contains(test_guard.pos) {
-            test_guard.advance(&test_obstacles);
-            iterations += 1;
-            if iterations > 5000 {
-                loops += 1;
-                break;
+    solvers.spawn(Day6::DAY, Part::B, move |progress| {
+        progress.start(candidates.len());
+        let mut loops = 0;
+        for candidate in candidates {
+            let mut test_guard = circuit_testing_guard.clone();
+            let mut test_obstacles = circuit_testing_obstacles.clone();
+            test_obstacles.insert(candidate, true);
+            // Being in the same place facing the same way twice means the guard is going round in
+            // circles, however long the loop is.
+            let mut seen = HashSet::new();
+            while bounds.contains(test_guard.pos) {
+                if !seen.insert((test_guard.pos, test_guard.facing.clone())) {
+                    loops += 1;
+                    break;
+                }
+                test_guard.advance(&test_obstacles);
+            }
+            if !progress.step() {
+                return None;
             }
         }
-    }
-    dbg!(loops);
+        Some(loops)
+    });
 }
 
-const fn vis(
-    _asset_server: Res<AssetServer>,
-    _commands: Commands,
-    _day6: Single<Entity, With<Day6>>,
+fn vis(mut commands: Commands, area_map: Res<AreaMap>, day6: Single<Entity, With<Day6>>) {
+    commands.entity(*day6).with_children(|p| {
+        p.spawn((Patrol::record(&area_map), Text::default()));
+    });
+}
+
+// Draws the area centred on the screen, with one square per position.
+fn draw_patrol(
+    mut gizmos: Gizmos,
+    mut timeline: Single<(&Timeline<Patrol>, &mut Text)>,
+    window: Single<&Window>,
 ) {
+    let (timeline, text) = &mut *timeline;
+    let patrol = timeline.current();
+    text.0 = format!(
+        "Step {} of {}: {} positions visited",
+        timeline.position(),
+        timeline.steps(),
+        patrol.guard.visited.len()
+    );
+
+    let size = patrol.bounds.size().as_vec2() + Vec2::ONE;
+    let cell = (window.height() * 0.8 / size.y).min(window.width() * 0.8 / size.x);
+    let to_world = |pos: IVec2| (pos.as_vec2() - (size - Vec2::ONE) / 2.) * Vec2::new(cell, -cell);
+    let square = Vec2::splat(cell * 0.8);
+
+    for pos in &patrol.obstacles {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(to_world(*pos)),
+            square,
+            DIM_GRAY,
+        );
+    }
+    for pos in &patrol.guard.visited {
+        gizmos.rect_2d(Isometry2d::from_translation(to_world(*pos)), square, GOLD);
+    }
+    gizmos.rect_2d(
+        Isometry2d::from_translation(to_world(patrol.guard.pos)),
+        square,
+        ORANGE_RED,
+    );
+}
+
+// The guard's walk, skipping steps to fit in the frames asked for.
+fn draw_frames(frames: In<usize>, area_map: Res<AreaMap>) -> Vec<Frame> {
+    let mut timeline = Patrol::record(&area_map);
+    export::sample(timeline.steps(), *frames)
+        .map(|step| {
+            timeline.seek(step);
+            timeline.current().frame()
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -218,24 +350,10 @@ mod tests {
 
     #[test]
     fn test_a() -> miette::Result<()> {
-        let input: Vec<&str> = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#..."
-            .lines()
-            .collect();
+        let input: Grid<char> = include_str!("../../assets/sample/6.aoc").parse()?;
         let mut guard = find_guard(&input)?;
         let obstacles = find_obstacles(&input)?;
-        let bounds = IRect {
-            min: IVec2::ZERO,
-            max: IVec2::new(9, 9),
-        };
+        let bounds = input.bounds();
         while bounds.contains(guard.pos) {
             guard.advance(&obstacles);
         }
@@ -244,29 +362,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_patrol_matches_batch() -> miette::Result<()> {
+        let input: Grid<char> = include_str!("../../assets/sample/6.aoc").parse()?;
+        let area_map = AreaMap {
+            obstacles: find_obstacles(&input)?,
+            bounds: input.bounds(),
+            start: find_guard(&input)?.pos,
+        };
+        let mut timeline = Patrol::record(&area_map);
+        while timeline.step_forward() {}
+        assert_eq!(41, timeline.current().guard.visited.len());
+
+        // Scrubbing back lands on the same state as stepping there from the start.
+        let mut stepped = Patrol::record(&area_map);
+        for _ in 0..10 {
+            stepped.step_forward();
+        }
+        timeline.seek(10);
+        assert_eq!(timeline.current().guard.pos, stepped.current().guard.pos);
+        assert_eq!(
+            timeline.current().guard.visited,
+            stepped.current().guard.visited
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_frame_shows_whole_walk() -> miette::Result<()> {
+        let input: Grid<char> = include_str!("../../assets/sample/6.aoc").parse()?;
+        let area_map = AreaMap {
+            obstacles: find_obstacles(&input)?,
+            bounds: input.bounds(),
+            start: find_guard(&input)?.pos,
+        };
+        let mut timeline = Patrol::record(&area_map);
+        timeline.seek(timeline.steps());
+        let frame = timeline.current().frame();
+        assert_eq!((frame.cells.width(), frame.cells.height()), (10, 10));
+        assert_eq!(41, frame.cells.find_all(&Cell::Visited).count());
+        assert_eq!(8, frame.cells.find_all(&Cell::Wall).count());
+        Ok(())
+    }
+
     #[test]
     fn test_b() -> miette::Result<()> {
-        let input: Vec<&str> = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#..."
-            .lines()
-            .collect();
+        let input: Grid<char> = include_str!("../../assets/sample/6.aoc").parse()?;
         let mut guard = find_guard(&input)?;
         let circuit_testing_guard = guard.clone();
 
         let obstacles = find_obstacles(&input)?;
         let circuit_testing_obstacles = obstacles.clone();
-        let bounds = IRect {
-            min: IVec2::ZERO,
-            max: IVec2::new(9, 9),
-        };
+        let bounds = input.bounds();
         while bounds.contains(guard.pos) {
             guard.advance(&obstacles);
         }
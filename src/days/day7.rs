@@ -2,49 +2,47 @@
 
 use std::ops::{Add, AddAssign, Mul, MulAssign};
 
-use crate::{loading::PuzzleInputs, puzzle_input_string_asset::PuzzleInputStringAsset, AoCState};
-use bevy::prelude::*;
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::{line_span, Failures, InputError},
+    loading::PuzzleInputs,
+    puzzle_input_string_asset::PuzzleInputStringAsset,
+    tasks::Solvers,
+};
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
 use chumsky::prelude::*;
 use itertools::Itertools;
 use miette::miette;
 use text::newline;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<Equations>().add_systems(
-        OnEnter(AoCState::Day7),
-        (init, process, solve_a, solve_b, vis).chain(),
-    );
+    app.init_day_resource::<Day7, Equations>().add_day::<Day7>();
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day7;
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day7"),
-            Day7,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day7),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 7: Bridge Repair"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Bridge Repair";
+    type Input = PuzzleInputStringAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -54,40 +52,40 @@ enum Equation {
 }
 
 impl Add for Equation {
-    type Output = Equation;
+    type Output = Self;
 
-    fn add(self, other: Equation) -> Equation {
+    fn add(self, other: Self) -> Self {
         match (self, other) {
-            (Equation::Number(lhs), Equation::Number(rhs)) => Equation::Number(lhs + rhs),
+            (Self::Number(lhs), Self::Number(rhs)) => Self::Number(lhs + rhs),
             _ => panic!("can't handle mismatched Equation variants"),
         }
     }
 }
 
 impl AddAssign for Equation {
-    fn add_assign(&mut self, other: Equation) {
+    fn add_assign(&mut self, other: Self) {
         *self = match (&*self, other) {
-            (Equation::Number(lhs), Equation::Number(rhs)) => Equation::Number(lhs + rhs),
+            (Self::Number(lhs), Self::Number(rhs)) => Self::Number(lhs + rhs),
             _ => panic!("can't handle mismatched Equation variants"),
         };
     }
 }
 
 impl Mul for Equation {
-    type Output = Equation;
+    type Output = Self;
 
-    fn mul(self, other: Equation) -> Equation {
+    fn mul(self, other: Self) -> Self {
         match (self, other) {
-            (Equation::Number(lhs), Equation::Number(rhs)) => Equation::Number(lhs * rhs),
+            (Self::Number(lhs), Self::Number(rhs)) => Self::Number(lhs * rhs),
             _ => panic!("can't handle mismatched Equation variants"),
         }
     }
 }
 
 impl MulAssign for Equation {
-    fn mul_assign(&mut self, other: Equation) {
+    fn mul_assign(&mut self, other: Self) {
         *self = match (&*self, other) {
-            (Equation::Number(lhs), Equation::Number(rhs)) => Equation::Number(lhs * rhs),
+            (Self::Number(lhs), Self::Number(rhs)) => Self::Number(lhs * rhs),
             _ => panic!("can't handle mismatched Equation variants"),
         };
     }
@@ -98,20 +96,28 @@ struct Equations {
     candidates: Vec<Vec<Equation>>,
 }
 
+// Numbers too big for an `i64` are reported rather than panicked on.
+fn int() -> impl Parser<char, i64, Error = Simple<char>> {
+    text::int(10).try_map(|s: String, span| {
+        s.parse()
+            .map_err(|e| Simple::custom(span, format!("{s} doesn't fit in an i64: {e}")))
+    })
+}
+
 fn test_value() -> impl Parser<char, Equation, Error = Simple<char>> {
-    text::int(10)
-        .map(|s: String| Equation::TestValue(s.parse().unwrap()))
+    int()
+        .map(Equation::TestValue)
         .then_ignore(just(':'))
-        .padded()
+        .then_ignore(just(' ').repeated())
 }
 
 fn number() -> impl Parser<char, Equation, Error = Simple<char>> {
-    text::int(10).map(|s: String| Equation::Number(s.parse().unwrap()))
+    int().map(Equation::Number)
 }
 
 fn equation() -> impl Parser<char, Vec<Equation>, Error = Simple<char>> {
     test_value()
-        .then(number().separated_by(just(' ')))
+        .then(number().separated_by(just(' ')).at_least(1))
         .map(|(t, n)| {
             let mut eq = vec![t];
             eq.extend(n);
@@ -119,20 +125,49 @@ fn equation() -> impl Parser<char, Vec<Equation>, Error = Simple<char>> {
         })
 }
 
+// Every line has to be an equation, so a malformed one is reported instead of ending the input
+// early.
 fn parser() -> impl Parser<char, Vec<Vec<Equation>>, Error = Simple<char>> {
-    equation().separated_by(newline()).collect()
+    equation()
+        .separated_by(newline())
+        .allow_trailing()
+        .padded()
+        .then_ignore(end())
+}
+
+// Chumsky's spans count chars rather than bytes, which is the same thing for an ASCII puzzle
+// input. The whole line is labelled, as the char it stopped at is often just past the problem.
+fn input_error(input: &str, e: &Simple<char>) -> InputError {
+    let line = input[..e.span().start.min(input.len())]
+        .matches('\n')
+        .count();
+    InputError::new(
+        Day7::DAY,
+        input,
+        line_span(input, line),
+        "Malformed calibration equation",
+        e.to_string(),
+    )
 }
 
 fn process(
     mut equations: ResMut<Equations>,
     puzzle_assets: Res<Assets<PuzzleInputStringAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.seven) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day7>(&puzzle_assets) else {
         return;
     };
 
-    equations.candidates = parser().parse(puzzle.0.to_owned()).unwrap();
+    match parser().parse(puzzle.0.as_str()) {
+        Ok(candidates) => equations.candidates = candidates,
+        Err(errors) => {
+            for e in errors {
+                failures.report(Day7::DAY, input_error(&puzzle.0, &e));
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -169,7 +204,7 @@ fn all_operators(length: usize) -> Vec<Vec<Operation>> {
 fn eval_if_valid(eq: &[Equation]) -> miette::Result<i64> {
     if let Equation::TestValue(tv) = &eq[0] {
         let remaining_values: Vec<Equation> = eq[1..].into();
-        let ops = all_operators(remaining_values.len() - 1);
+        let ops = all_operators(remaining_values.len().saturating_sub(1));
         for candidate in ops {
             let mut total = 0;
             for i in 0..remaining_values.len() {
@@ -200,7 +235,7 @@ fn eval_if_valid_concat(eq: &[Equation]) -> miette::Result<i64> {
         return Err(miette!("TestValue parse fail"));
     };
     let mut values: Vec<Equation> = eq[1..].into();
-    let is_valid = (0..values.len() - 1)
+    let is_valid = (0..values.len().saturating_sub(1))
         .map(|_| [Operation::Add, Operation::Multiply, Operation::Concatenate])
         .multi_cartesian_product()
         .any(|op_sequence| {
@@ -232,20 +267,29 @@ fn eval_if_valid_concat(eq: &[Equation]) -> miette::Result<i64> {
     }
 }
 
-fn solve_a(equations: Res<Equations>) {
+fn solve_a(mut answers: Answers, equations: Res<Equations>) {
     let mut actual = 0;
     for equation in &equations.candidates {
         actual += eval_if_valid(equation).unwrap();
     }
-    dbg!(actual);
+    answers.send(Day7::DAY, Part::A, actual);
 }
 
-fn solve_b(equations: Res<Equations>) {
-    let mut actual = 0;
-    for equation in &equations.candidates {
-        actual += eval_if_valid_concat(equation).unwrap();
-    }
-    dbg!(actual);
+// Trying every sequence of three operators is slow for the longer equations, so this part is
+// solved in the background.
+fn solve_b(mut solvers: Solvers, equations: Res<Equations>) {
+    let candidates = equations.candidates.clone();
+    solvers.spawn(Day7::DAY, Part::B, move |progress| {
+        progress.start(candidates.len());
+        let mut actual = 0;
+        for equation in &candidates {
+            actual += eval_if_valid_concat(equation).unwrap();
+            if !progress.step() {
+                return None;
+            }
+        }
+        Some(actual)
+    });
 }
 
 const fn vis(
@@ -261,15 +305,7 @@ mod tests {
 
     #[test]
     fn test_a() -> miette::Result<()> {
-        let input = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+        let input = include_str!("../../assets/sample/7.aoc");
         let equations = parser()
             .parse(input)
             .map_err(|_| miette!("puzzle input parse failed!"))?;
@@ -286,15 +322,7 @@ mod tests {
 
     #[test]
     fn test_b() -> miette::Result<()> {
-        let input = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+        let input = include_str!("../../assets/sample/7.aoc");
         let equations = parser()
             .parse(input)
             .map_err(|_| miette!("puzzle input parse failed"))?;
@@ -306,4 +334,19 @@ mod tests {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_equations() {
+        // A test value without any numbers, and a number too big for an i64, on the second line.
+        for input in [
+            "190: 10 19\n5:\n3267: 81 40 27\n",
+            "190: 10 19\n99999999999999999999: 1 2\n",
+        ] {
+            let errors = parser().parse(input).unwrap_err();
+            let error = miette::Report::new(input_error(input, &errors[0]));
+            let label = error.labels().unwrap().next().unwrap();
+            let span = line_span(input, 1);
+            assert_eq!((label.offset(), label.len()), (span.offset(), span.len()));
+        }
+    }
 }
@@ -1,87 +1,78 @@
 #![allow(dead_code, unused)]
 
-use crate::{loading::PuzzleInputs, puzzle_input_string_asset::PuzzleInputStringAsset, AoCState};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    export::{self, Cell, Frame},
+    grid::Grid,
+    loading::PuzzleInputs,
+    puzzle_input_grid_asset::PuzzleInputGridAsset,
+};
 use bevy::{
+    ecs::schedule::SystemConfigs,
     prelude::*,
     utils::{HashMap, HashSet},
 };
 use miette::miette;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<CityMap>().add_systems(
-        OnEnter(AoCState::Day8),
-        (init, process, solve_a, solve_b, vis).chain(),
-    );
+    app.init_day_resource::<Day8, CityMap>()
+        .add_day::<Day8>()
+        .add_export::<Day8, _>(draw_frames);
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day8;
 
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+    type Input = PuzzleInputGridAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
+}
+
 #[derive(Debug, Clone, Default, Resource)]
 struct CityMap {
     pub antennae: HashMap<char, Vec<IVec2>>,
     pub antinodes: Vec<IVec2>,
-}
-
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day8"),
-            Day8,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day8),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 8: Resonant Collinearity"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+    pub bounds: IRect,
 }
 
 fn process(
     mut city_map: ResMut<CityMap>,
-    puzzle_assets: Res<Assets<PuzzleInputStringAsset>>,
+    puzzle_assets: Res<Assets<PuzzleInputGridAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.eight) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day8>(&puzzle_assets) else {
         return;
     };
-    let input: Vec<&str> = puzzle.0.lines().collect();
-    city_map.antennae = find_antennae(&input);
-    city_map.antinodes = find_antinodes(
-        &city_map.antennae,
-        &IRect {
-            min: IVec2::ZERO,
-            max: IVec2::new((input[0].len() - 1) as i32, (input.len() - 1) as i32),
-        },
-    );
+    city_map.bounds = puzzle.0.bounds();
+    city_map.antennae = find_antennae(&puzzle.0);
+    city_map.antinodes = find_antinodes(&city_map.antennae, &city_map.bounds);
 }
 
-fn find_antennae(map: &[&str]) -> HashMap<char, Vec<IVec2>> {
+fn find_antennae(map: &Grid<char>) -> HashMap<char, Vec<IVec2>> {
     let mut antennae: HashMap<char, Vec<IVec2>> = [].into();
-    for (y, row) in map.iter().enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            if c == '.' {
-                continue;
-            }
-            antennae
-                .entry(c)
-                .or_default()
-                .push(IVec2::new(x as i32, y as i32));
+    for (pos, c) in map.iter() {
+        if *c == '.' {
+            continue;
         }
+        antennae.entry(*c).or_default().push(pos);
     }
     antennae
 }
@@ -146,19 +137,13 @@ fn find_antinodes2(antennae: &HashMap<char, Vec<IVec2>>, bounds: &IRect) -> Vec<
     antinodes.into_iter().collect()
 }
 
-fn solve_a(city_map: Res<CityMap>) {
-    dbg!(city_map.antinodes.len());
+fn solve_a(mut answers: Answers, city_map: Res<CityMap>) {
+    answers.send(Day8::DAY, Part::A, city_map.antinodes.len());
 }
 
-fn solve_b(city_map: Res<CityMap>) {
-    dbg!(find_antinodes2(
-        &city_map.antennae,
-        &IRect {
-            min: IVec2::ZERO,
-            max: IVec2::new(49, 49),
-        },
-    )
-    .len());
+fn solve_b(mut answers: Answers, city_map: Res<CityMap>) {
+    let antinodes = find_antinodes2(&city_map.antennae, &city_map.bounds);
+    answers.send(Day8::DAY, Part::B, antinodes.len());
 }
 
 const fn vis(
@@ -168,64 +153,44 @@ const fn vis(
 ) {
 }
 
+// Two frames, with the antinodes from part A and then the extra ones from part B.
+fn draw_frames(_frames: In<usize>, city_map: Res<CityMap>) -> Vec<Frame> {
+    let draw = |antinodes: &[IVec2]| {
+        let mut frame = export::blank(city_map.bounds);
+        for pos in antinodes {
+            frame[*pos] = Cell::Match;
+        }
+        for pos in city_map.antennae.values().flatten() {
+            frame[*pos] = Cell::Marker;
+        }
+        frame
+    };
+    vec![
+        draw(&city_map.antinodes),
+        draw(&find_antinodes2(&city_map.antennae, &city_map.bounds)),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_a() -> miette::Result<()> {
-        let input: Vec<&str> = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............"
-            .lines()
-            .collect();
+        let input: Grid<char> = include_str!("../../assets/sample/8.aoc").parse()?;
         let expected = 14;
         let a = find_antennae(&input);
-        let ant = find_antinodes(
-            &a,
-            &IRect {
-                min: IVec2::ZERO,
-                max: IVec2::new(11, 11),
-            },
-        );
+        let ant = find_antinodes(&a, &input.bounds());
         assert_eq!(expected, ant.len());
         Ok(())
     }
 
     #[test]
     fn test_b() -> miette::Result<()> {
-        let input: Vec<&str> = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............"
-            .lines()
-            .collect();
+        let input: Grid<char> = include_str!("../../assets/sample/8.aoc").parse()?;
         let expected = 34;
         let a = find_antennae(&input);
-        let ant = find_antinodes2(
-            &a,
-            &IRect {
-                min: IVec2::ZERO,
-                max: IVec2::new(11, 11),
-            },
-        );
+        let ant = find_antinodes2(&a, &input.bounds());
         assert_eq!(expected, ant.len());
         Ok(())
     }
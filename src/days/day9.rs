@@ -1,66 +1,81 @@
 #![allow(dead_code, unused)]
 
-use crate::{loading::PuzzleInputs, puzzle_input_string_asset::PuzzleInputStringAsset, AoCState};
+use crate::{
+    answer::{Answers, Part},
+    days::{AddDay, Solution},
+    failure::Failures,
+    loading::PuzzleInputs,
+    puzzle_input_string_asset::PuzzleInputStringAsset,
+    timeline::{Replay, Timeline},
+};
 use bevy::{
+    color::palettes::css::DIM_GRAY,
+    ecs::schedule::SystemConfigs,
     prelude::*,
     utils::{HashMap, HashSet},
 };
-use miette::miette;
+use miette::{miette, LabeledSpan, NamedSource};
+use serde::{Deserialize, Serialize};
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<FileMap>().add_systems(
-        OnEnter(AoCState::Day9),
-        (init, process, solve_a, solve_b, vis).chain(),
-    );
+    app.init_day_resource::<Day9, FileMap>()
+        .add_day::<Day9>()
+        .add_timeline::<Day9, Disk, _>(draw_disk);
 }
 
 #[derive(Resource, Default)]
 struct FileMap {
+    /// The blocks as the disk map describes them, before compacting.
+    layout: Vec<Option<u64>>,
     blocks: Vec<Option<u64>>,
 }
 
-#[derive(Component)]
+#[derive(Component, Default)]
 struct Day9;
 
-fn init(mut commands: Commands) {
-    commands
-        .spawn((
-            Name::new("Day9"),
-            Day9,
-            Node {
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Start,
-                justify_self: JustifySelf::Center,
-                padding: UiRect::all(Val::Px(10.)),
-                width: Val::Percent(100.),
-                ..default()
-            },
-            StateScoped(AoCState::Day9),
-        ))
-        .with_children(|p| {
-            p.spawn((
-                Text::new("Day 9: Disk Fragmenter"),
-                TextFont {
-                    font_size: 30.,
-                    ..default()
-                },
-            ));
-        });
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
+    type Input = PuzzleInputStringAsset;
+
+    fn process() -> SystemConfigs {
+        process.into_configs()
+    }
+
+    fn solve_a() -> SystemConfigs {
+        solve_a.into_configs()
+    }
+
+    fn solve_b() -> SystemConfigs {
+        solve_b.into_configs()
+    }
+
+    fn vis() -> SystemConfigs {
+        vis.into_configs()
+    }
 }
 
 fn process(
     mut file_map: ResMut<FileMap>,
     puzzle_assets: Res<Assets<PuzzleInputStringAsset>>,
     puzzle_inputs: Res<PuzzleInputs>,
+    mut failures: Failures,
 ) {
-    let Some(puzzle) = puzzle_assets.get(&puzzle_inputs.nine) else {
+    let Some(puzzle) = puzzle_inputs.get::<Day9>(&puzzle_assets) else {
         return;
     };
 
     let mut blocks: Vec<char> = puzzle.0.chars().collect();
-    file_map.blocks = get_file_blocks(&blocks).unwrap();
+    file_map.layout = match get_file_blocks(&blocks) {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            // The disk map is ASCII, so the char index in the error's label is also its offset.
+            let input = NamedSource::new(format!("day {} input", Day9::DAY), puzzle.0.clone());
+            failures.report(Day9::DAY, e.with_source_code(input));
+            return;
+        }
+    };
+    file_map.blocks = file_map.layout.clone();
     compact_blocks(&mut file_map.blocks);
 }
 
@@ -70,9 +85,13 @@ fn get_file_blocks(disk_map: &[char]) -> miette::Result<Vec<Option<u64>>> {
         if *c == '\n' {
             continue;
         }
-        let val = c
-            .to_digit(10)
-            .ok_or_else(|| miette!("couldn't parse char to int: {}", c))?;
+        let val = c.to_digit(10).ok_or_else(|| {
+            miette!(
+                labels = vec![LabeledSpan::at(i..i + 1, "not a digit")],
+                "couldn't parse char to int: {}",
+                c
+            )
+        })?;
         let mut block: Option<u64> = if i % 2 == 0 {
             // This is a file length
             let mut id = if i != 0 { i as u64 / 2 } else { 0 };
@@ -102,45 +121,149 @@ fn get_file_blocks(disk_map: &[char]) -> miette::Result<Vec<Option<u64>>> {
 //     None
 // }
 
-fn compact_blocks(blocks: &mut [Option<u64>]) -> miette::Result<()> {
-    let mut i = 0;
-    let mut j = blocks.len();
-
-    while i < blocks.len() {
-        if blocks[i].is_none() {
-            while j > i {
-                j -= 1;
-                if blocks[j].is_some() {
+/// Moves file blocks from the end of the disk into the gaps nearest its start, one at a time.
+#[derive(Clone, Debug)]
+struct Compactor {
+    i: usize,
+    j: usize,
+}
+
+/// A file block moving into a gap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Move {
+    from: u32,
+    to: u32,
+}
+
+impl Compactor {
+    const fn new(blocks: &[Option<u64>]) -> Self {
+        Self {
+            i: 0,
+            j: blocks.len(),
+        }
+    }
+
+    /// Moves the last file block into the first gap, returning `None` once there are no gaps
+    /// left before a file block.
+    fn next_move(&mut self, blocks: &mut [Option<u64>]) -> Option<Move> {
+        while self.i < blocks.len() {
+            let i = self.i;
+            self.i += 1;
+            if blocks[i].is_some() {
+                continue;
+            }
+            while self.j > i {
+                self.j -= 1;
+                if blocks[self.j].is_some() {
                     break;
                 }
             }
-            if j > i {
-                blocks[i] = blocks[j];
-                blocks[j] = None;
+            if self.j > i {
+                blocks[i] = blocks[self.j];
+                blocks[self.j] = None;
+                return Some(Move {
+                    from: self.j as u32,
+                    to: i as u32,
+                });
             }
         }
-        i += 1;
+        None
     }
+}
 
+fn compact_blocks(blocks: &mut [Option<u64>]) -> miette::Result<()> {
+    let mut compactor = Compactor::new(blocks);
+    while compactor.next_move(blocks).is_some() {}
     Ok(())
 }
 
-fn solve_a(file_map: Res<FileMap>) {
-    dbg!(file_map
-        .blocks
+fn checksum(blocks: &[Option<u64>]) -> u64 {
+    blocks
         .iter()
         .filter_map(|x| *x)
         .enumerate()
-        .fold(0, |acc, (i, block)| acc + i as u64 * block));
+        .fold(0, |acc, (i, block)| acc + i as u64 * block)
+}
+
+fn solve_a(mut answers: Answers, file_map: Res<FileMap>) {
+    answers.send(Day9::DAY, Part::A, checksum(&file_map.blocks));
 }
 
 const fn solve_b() {}
 
-const fn vis(
-    _asset_server: Res<AssetServer>,
-    _commands: Commands,
-    _day9: Single<Entity, With<Day9>>,
+/// The disk during part A's compaction, as the state of a [`Timeline`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Disk {
+    blocks: Vec<Option<u64>>,
+}
+
+impl Replay for Disk {
+    type Step = Move;
+
+    fn apply(&mut self, step: &Move) {
+        self.blocks.swap(step.from as usize, step.to as usize);
+    }
+}
+
+impl Disk {
+    /// Compacts the disk as part A does, recording every move.
+    fn record(layout: &[Option<u64>]) -> Timeline<Self> {
+        let mut blocks = layout.to_vec();
+        let mut compactor = Compactor::new(&blocks);
+        let moves = std::iter::from_fn(|| compactor.next_move(&mut blocks)).collect();
+        Timeline::new(
+            Self {
+                blocks: layout.to_vec(),
+            },
+            moves,
+        )
+    }
+}
+
+fn vis(mut commands: Commands, file_map: Res<FileMap>, day9: Single<Entity, With<Day9>>) {
+    commands.entity(*day9).with_children(|p| {
+        p.spawn((Disk::record(&file_map.layout), Text::default()));
+    });
+}
+
+// Too many blocks to draw one square each without slowing everything down, as the real inputs
+// have.
+const MAX_DRAWN_BLOCKS: usize = 20_000;
+
+// Draws the disk as rows of squares, coloured by file.
+fn draw_disk(
+    mut gizmos: Gizmos,
+    mut timeline: Single<(&Timeline<Disk>, &mut Text)>,
+    window: Single<&Window>,
 ) {
+    let (timeline, text) = &mut *timeline;
+    let blocks = &timeline.current().blocks;
+    text.0 = if timeline.position() < timeline.steps() {
+        format!("Move {} of {}", timeline.position(), timeline.steps())
+    } else {
+        format!("Compacted, checksum {}", checksum(blocks))
+    };
+
+    if blocks.len() > MAX_DRAWN_BLOCKS {
+        return;
+    }
+
+    let columns = (blocks.len() as f32).sqrt().ceil().max(1.);
+    let rows = (blocks.len() as f32 / columns).ceil();
+    let cell = (window.width() * 0.8 / columns).min(window.height() * 0.6 / rows);
+    let origin = Vec2::new(-(columns - 1.) * cell / 2., (rows - 1.) * cell / 2.);
+    let square = Vec2::splat(cell * 0.8);
+    for (i, block) in blocks.iter().enumerate() {
+        let pos = origin
+            + Vec2::new(
+                (i as f32 % columns) * cell,
+                -(i as f32 / columns).floor() * cell,
+            );
+        let color = block.map_or(DIM_GRAY.into(), |id| {
+            Color::hsl((id * 47 % 360) as f32, 0.7, 0.5)
+        });
+        gizmos.rect_2d(Isometry2d::from_translation(pos), square, color);
+    }
 }
 
 #[cfg(test)]
@@ -149,7 +272,7 @@ mod tests {
 
     #[test]
     fn test_a() -> miette::Result<()> {
-        let input: Vec<char> = "2333133121414131402".chars().collect();
+        let input: Vec<char> = include_str!("../../assets/sample/9.aoc").chars().collect();
         let mut file_blocks = get_file_blocks(&input)?;
         compact_blocks(&mut file_blocks)?;
         let expected = 1928;
@@ -162,9 +285,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recorded_compaction_matches_batch() -> miette::Result<()> {
+        let input: Vec<char> = include_str!("../../assets/sample/9.aoc").chars().collect();
+        let layout = get_file_blocks(&input)?;
+        let mut timeline = Disk::record(&layout);
+        while timeline.step_forward() {}
+        assert_eq!(1928, checksum(&timeline.current().blocks));
+
+        // Scrubbing back to the start undoes every move.
+        timeline.seek(0);
+        assert_eq!(layout, timeline.current().blocks);
+        Ok(())
+    }
+
     #[test]
     fn test_b() -> miette::Result<()> {
-        let input: Vec<char> = "2333133121414131402".chars().collect();
+        let input: Vec<char> = include_str!("../../assets/sample/9.aoc").chars().collect();
         Ok(())
     }
 }
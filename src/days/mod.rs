@@ -1,4 +1,21 @@
-use bevy::prelude::*;
+use std::fmt;
+
+use bevy::{
+    ecs::schedule::{ScheduleLabel, SystemConfigs},
+    prelude::*,
+};
+
+use crate::{
+    answer::AnswerPanel,
+    export::{Exporters, Frame},
+    failure::FailedDays,
+    loading::PuzzleInputs,
+    simulation::{Simulated, SimulationClock, SimulationStep},
+    tasks::Solve,
+    timeline::{self, Replay},
+    timing::{finish_stage, start_stage, Stage, TimingPanel},
+    AoCState,
+};
 
 mod day1;
 mod day2;
@@ -10,16 +27,461 @@ mod day7;
 mod day8;
 mod day9;
 
-pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((
-        day1::plugin,
-        day2::plugin,
-        day3::plugin,
-        day4::plugin,
-        day5::plugin,
-        day6::plugin,
-        day7::plugin,
-        day8::plugin,
-        day9::plugin,
-    ));
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Days>()
+        .add_sub_state::<DayPhase>()
+        .add_systems(OnEnter(DayPhase::Parsing), run_phase(DayPhase::Parsing))
+        .add_systems(OnEnter(DayPhase::SolvingA), run_phase(DayPhase::SolvingA))
+        .add_systems(OnEnter(DayPhase::SolvingB), run_phase(DayPhase::SolvingB))
+        .add_systems(
+            OnEnter(DayPhase::Visualising),
+            run_phase(DayPhase::Visualising),
+        )
+        .add_systems(Update, (advance, show_phase).chain())
+        .add_plugins((
+            day1::plugin,
+            day2::plugin,
+            day3::plugin,
+            day4::plugin,
+            day5::plugin,
+            day6::plugin,
+            day7::plugin,
+            day8::plugin,
+            day9::plugin,
+        ));
+}
+
+/// A day's puzzle. Implemented by the marker component on the day's root UI node, and registered
+/// with [`AddDay::add_day`].
+pub trait Solution: Component + Default {
+    const DAY: u8;
+    const TITLE: &'static str;
+    /// The asset the day's puzzle input is loaded as.
+    type Input: Asset;
+
+    /// Starts loading the day's input from a file without a `.meta` file of its own, e.g. one
+    /// given to `batch` or `run --input`. Override it to load with the settings that the day's
+    /// `.meta` files in `assets` have.
+    fn load_input_without_meta(asset_server: &AssetServer, path: String) -> Handle<Self::Input> {
+        asset_server.load(path)
+    }
+
+    fn process() -> SystemConfigs;
+    fn solve_a() -> SystemConfigs;
+    fn solve_b() -> SystemConfigs;
+
+    /// Runs after both parts have been solved. Does nothing unless overridden.
+    fn vis() -> SystemConfigs {
+        (|| {}).into_configs()
+    }
+
+    fn state() -> AoCState {
+        AoCState::Day(Self::DAY)
+    }
+}
+
+/// What the rest of the app needs to know about a registered day.
+#[derive(Clone, Copy, Debug)]
+pub struct DayInfo {
+    pub number: u8,
+    pub title: &'static str,
+    load_input: fn(&AssetServer, String) -> UntypedHandle,
+    load_input_without_meta: fn(&AssetServer, String) -> UntypedHandle,
+}
+
+impl DayInfo {
+    pub fn load_input(&self, asset_server: &AssetServer, path: String) -> UntypedHandle {
+        (self.load_input)(asset_server, path)
+    }
+
+    /// Loads the input with [`Solution::load_input_without_meta`].
+    pub fn load_input_without_meta(
+        &self,
+        asset_server: &AssetServer,
+        path: String,
+    ) -> UntypedHandle {
+        (self.load_input_without_meta)(asset_server, path)
+    }
+}
+
+/// Every registered day, ordered by day number.
+#[derive(Resource, Default, Debug)]
+pub struct Days(Vec<DayInfo>);
+
+impl Days {
+    pub fn get(&self, day: u8) -> Option<&DayInfo> {
+        self.0.iter().find(|d| d.number == day)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DayInfo> {
+        self.0.iter()
+    }
+}
+
+/// Where the open day has got to. Each phase's systems run on entering it, and the day moves on
+/// to the next phase once they, and any parts they left solving in the background, are done.
+#[derive(SubStates, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[source(AoCState = AoCState::Day(_))]
+pub enum DayPhase {
+    #[default]
+    Parsing,
+    SolvingA,
+    SolvingB,
+    Visualising,
+    Done,
+    /// One of the other phases reported an error, which is kept in [`FailedDays`].
+    Failed,
+}
+
+impl DayPhase {
+    /// The phase after this one, if the day isn't finished.
+    pub const fn next(self) -> Option<Self> {
+        match self {
+            Self::Parsing => Some(Self::SolvingA),
+            Self::SolvingA => Some(Self::SolvingB),
+            Self::SolvingB => Some(Self::Visualising),
+            Self::Visualising => Some(Self::Done),
+            Self::Done | Self::Failed => None,
+        }
+    }
+
+    pub const fn is_finished(self) -> bool {
+        self.next().is_none()
+    }
+
+    /// The stage timed while in this phase, if any.
+    const fn stage(self) -> Option<Stage> {
+        match self {
+            Self::Parsing => Some(Stage::Process),
+            Self::SolvingA => Some(Stage::SolveA),
+            Self::SolvingB => Some(Stage::SolveB),
+            Self::Visualising | Self::Done | Self::Failed => None,
+        }
+    }
+}
+
+impl fmt::Display for DayPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Parsing => "Parsing",
+            Self::SolvingA => "Solving part A",
+            Self::SolvingB => "Solving part B",
+            Self::Visualising => "Visualising",
+            Self::Done => "Done",
+            Self::Failed => "Failed",
+        })
+    }
+}
+
+/// One phase of solving a day, from processing its input through to visualising the answers. Run
+/// on entering the phase while the day is open.
+#[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SolveDay(pub u8, pub DayPhase);
+
+/// Resets a day's working state before it is solved.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ResetDay;
+
+pub trait AddDay {
+    /// Registers a day, running its systems in order as it moves through each [`DayPhase`]. If
+    /// one of them reports an error, the day moves to [`DayPhase::Failed`] and the rest are
+    /// skipped.
+    fn add_day<S: Solution>(&mut self) -> &mut Self;
+
+    /// Adds a resource holding some of a day's working state, which goes back to its default
+    /// each time the day is solved and when it is left, so nothing carries over between visits.
+    /// Anything else a day spawns should be [`StateScoped`] to its state.
+    fn init_day_resource<S: Solution, R: Resource + Default>(&mut self) -> &mut Self;
+
+    /// Adds systems that replay part of a day a few steps at a time, running every frame while
+    /// the day is open. They should take [`SimulationClock::steps`] steps each frame, and the day
+    /// gets controls for playing, pausing and stepping through them. Apps without the simulation
+    /// plugin, like the headless one, skip them.
+    fn add_simulation<S: Solution, M>(&mut self, systems: impl IntoSystemConfigs<M>) -> &mut Self;
+
+    /// Adds a simulation that plays through a recorded [`Timeline`](crate::timeline::Timeline),
+    /// with a scrubber for moving back and forth along it and buttons for saving and loading it.
+    /// The day spawns the timeline itself, usually in `vis`, and `draw` shows its current state
+    /// each frame.
+    fn add_timeline<S: Solution, R: Replay, M>(
+        &mut self,
+        draw: impl IntoSystemConfigs<M>,
+    ) -> &mut Self;
+
+    /// Adds a system that draws a solved day as frames for the `export` command, given the most
+    /// frames it should draw. It runs once, after the day has been solved without a window.
+    fn add_export<S: Solution, M>(
+        &mut self,
+        frames: impl IntoSystem<In<usize>, Vec<Frame>, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl AddDay for App {
+    fn add_day<S: Solution>(&mut self) -> &mut Self {
+        let mut days = self.world_mut().get_resource_or_init::<Days>();
+        let index = days.0.partition_point(|d| d.number < S::DAY);
+        days.0.insert(
+            index,
+            DayInfo {
+                number: S::DAY,
+                title: S::TITLE,
+                load_input: load_input::<S::Input>,
+                load_input_without_meta: load_input_without_meta::<S>,
+            },
+        );
+
+        self.add_systems(
+            SolveDay(S::DAY, DayPhase::Parsing),
+            (
+                init::<S>,
+                start_stage,
+                S::process(),
+                finish_stage(S::DAY, Stage::Process),
+            )
+                .chain()
+                .after(ResetDay),
+        )
+        .add_systems(
+            SolveDay(S::DAY, DayPhase::SolvingA),
+            (
+                start_stage,
+                S::solve_a(),
+                finish_stage(S::DAY, Stage::SolveA),
+            )
+                .chain(),
+        )
+        .add_systems(
+            SolveDay(S::DAY, DayPhase::SolvingB),
+            (
+                start_stage,
+                S::solve_b(),
+                finish_stage(S::DAY, Stage::SolveB),
+            )
+                .chain(),
+        )
+        .add_systems(SolveDay(S::DAY, DayPhase::Visualising), S::vis())
+        .add_systems(OnEnter(S::state()), restart)
+        .add_systems(Update, reload::<S>.run_if(in_state(S::state())))
+    }
+
+    fn init_day_resource<S: Solution, R: Resource + Default>(&mut self) -> &mut Self {
+        self.init_resource::<R>()
+            .add_systems(
+                SolveDay(S::DAY, DayPhase::Parsing),
+                reset::<R>.in_set(ResetDay),
+            )
+            .add_systems(OnExit(S::state()), reset::<R>)
+    }
+
+    fn add_simulation<S: Solution, M>(&mut self, systems: impl IntoSystemConfigs<M>) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<Simulated>()
+            .0
+            .insert(S::DAY);
+        self.add_systems(
+            Update,
+            systems
+                .in_set(SimulationStep)
+                .run_if(in_state(S::state()).and(resource_exists::<SimulationClock>)),
+        )
+    }
+
+    fn add_timeline<S: Solution, R: Replay, M>(
+        &mut self,
+        draw: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        self.add_simulation::<S, _>(
+            (
+                timeline::spawn_scrubber::<R>(S::DAY),
+                timeline::play::<R>,
+                timeline::show_progress::<R>,
+                draw,
+            )
+                .chain(),
+        )
+    }
+
+    fn add_export<S: Solution, M>(
+        &mut self,
+        frames: impl IntoSystem<In<usize>, Vec<Frame>, M> + 'static,
+    ) -> &mut Self {
+        let id = self.register_system(frames);
+        self.world_mut()
+            .get_resource_or_init::<Exporters>()
+            .0
+            .insert(S::DAY, id);
+        self
+    }
+}
+
+fn reset<R: Resource + Default>(world: &mut World) {
+    world.insert_resource(R::default());
+}
+
+fn run_phase(phase: DayPhase) -> impl FnMut(&mut World) {
+    move |world| {
+        if let AoCState::Day(day) = *world.resource::<State<AoCState>>().get() {
+            world.run_schedule(SolveDay(day, phase));
+        }
+    }
+}
+
+// Going straight from one day to another keeps the last day's phase, so start the new one again
+// from the beginning. Coming from the menu, it already starts there.
+fn restart(phase: Option<Res<State<DayPhase>>>, mut next_phase: ResMut<NextState<DayPhase>>) {
+    if phase.is_some_and(|phase| *phase.get() != DayPhase::Parsing) {
+        next_phase.set(DayPhase::Parsing);
+    }
+}
+
+// Moves the open day on a phase at a time, or on to `Failed` as soon as it reports an error.
+fn advance(
+    state: Res<State<AoCState>>,
+    phase: Option<Res<State<DayPhase>>>,
+    mut next_phase: ResMut<NextState<DayPhase>>,
+    failed: Res<FailedDays>,
+    solves: Query<&Solve>,
+) {
+    let (AoCState::Day(day), Some(phase)) = (*state.get(), phase) else {
+        return;
+    };
+    // Another day has just been opened, and is about to start over.
+    if matches!(*next_phase, NextState::Pending(_)) {
+        return;
+    }
+    let Some(next) = phase.next() else {
+        return;
+    };
+    if failed.get(day).is_some() {
+        next_phase.set(DayPhase::Failed);
+        return;
+    }
+    let stage = phase.stage();
+    if solves
+        .iter()
+        .any(|solve| stage.is_some_and(|stage| solve.stage() == (day, stage)))
+    {
+        return;
+    }
+    next_phase.set(next);
+}
+
+/// The line under a day's title saying which [`DayPhase`] it is in.
+#[derive(Component, Debug)]
+struct PhaseLabel;
+
+fn show_phase(
+    state: Res<State<AoCState>>,
+    phase: Option<Res<State<DayPhase>>>,
+    failed: Res<FailedDays>,
+    mut labels: Query<&mut Text, With<PhaseLabel>>,
+) {
+    let (AoCState::Day(day), Some(phase)) = (*state.get(), phase) else {
+        return;
+    };
+    let label = match (phase.get(), failed.get(day)) {
+        (DayPhase::Failed, Some(report)) => format!("Failed: {report}"),
+        (phase, _) => phase.to_string(),
+    };
+    for mut text in &mut labels {
+        if text.0 != label {
+            text.0.clone_from(&label);
+        }
+    }
+}
+
+// Solves the day again when its input is modified, so a new input or a tweaked sample shows up
+// without leaving the day.
+fn reload<S: Solution>(
+    mut events: EventReader<AssetEvent<S::Input>>,
+    puzzle_inputs: Res<PuzzleInputs>,
+    scoped: Query<(Entity, &StateScoped<AoCState>)>,
+    mut next_phase: ResMut<NextState<DayPhase>>,
+    mut commands: Commands,
+) {
+    let Some(id) = puzzle_inputs.id::<S>() else {
+        return;
+    };
+    if events.read().filter(|event| event.is_modified(id)).count() == 0 {
+        return;
+    }
+
+    info!("Day {} input changed, solving it again", S::DAY);
+    for (entity, scope) in &scoped {
+        if scope.0 == S::state() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    next_phase.set(DayPhase::Parsing);
+}
+
+fn load_input<A: Asset>(asset_server: &AssetServer, path: String) -> UntypedHandle {
+    asset_server.load::<A>(path).untyped()
+}
+
+fn load_input_without_meta<S: Solution>(asset_server: &AssetServer, path: String) -> UntypedHandle {
+    S::load_input_without_meta(asset_server, path).untyped()
+}
+
+/// Marks the root UI node of a day, for things that don't know its [`Solution`] type.
+#[derive(Component, Debug)]
+pub struct DayRoot(pub u8);
+
+fn init<S: Solution>(mut commands: Commands, mut failed: ResMut<FailedDays>) {
+    // Give the day a fresh start, in case its input has been fixed since it last failed.
+    failed.clear(S::DAY);
+
+    commands
+        .spawn((
+            Name::new(format!("Day{}", S::DAY)),
+            S::default(),
+            DayRoot(S::DAY),
+            Node {
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Start,
+                justify_self: JustifySelf::Center,
+                padding: UiRect::all(Val::Px(10.)),
+                width: Val::Percent(100.),
+                ..default()
+            },
+            StateScoped(S::state()),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(format!("Day {}: {}", S::DAY, S::TITLE)),
+                TextFont {
+                    font_size: 30.,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                PhaseLabel,
+                Text::default(),
+                TextFont {
+                    font_size: 14.,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Name::new("Answers"),
+                AnswerPanel(S::DAY),
+                Node {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    margin: UiRect::vertical(Val::Px(10.)),
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Name::new("Timings"),
+                TimingPanel(S::DAY),
+                Node {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+            ));
+        });
 }
@@ -0,0 +1,70 @@
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+    utils::HashMap,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::answer::Part;
+
+/// Accepted answers, keyed by day.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+// The files are just the map, without the struct's name or parentheses around it.
+#[serde(transparent)]
+pub struct ExpectedAnswersAsset(HashMap<u8, DayAnswers>);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DayAnswers {
+    a: Option<String>,
+    b: Option<String>,
+}
+
+impl ExpectedAnswersAsset {
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        let answers = self.0.get(&day)?;
+        match part {
+            Part::A => answers.a.as_deref(),
+            Part::B => answers.b.as_deref(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ExpectedAnswersAssetLoader;
+
+/// Possible errors that can be produced by [`ExpectedAnswersAssetLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ExpectedAnswersAssetLoaderError {
+    /// An [IO](std::io) Error
+    #[error("Could not load asset: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A [RON](ron) Error
+    #[error("Could not parse RON: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for ExpectedAnswersAssetLoader {
+    type Asset = ExpectedAnswersAsset;
+    type Settings = ();
+    type Error = ExpectedAnswersAssetLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
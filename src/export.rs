@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    ops::{Index, IndexMut},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    color::palettes::css::{BLACK, DIM_GRAY, GOLD, LIME, ORANGE_RED},
+    ecs::system::SystemId,
+    prelude::*,
+    utils::HashMap,
+};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Rgba, RgbaImage,
+};
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::grid::Grid;
+
+// How long each frame of an animated GIF is shown for.
+const FRAME_DELAY_MS: u32 = 80;
+
+/// What a cell of an exported frame shows. Each class is drawn in its own colour, matching the
+/// colours the days use on screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Wall,
+    Visited,
+    Match,
+    Marker,
+}
+
+impl Cell {
+    pub const fn color(self) -> Srgba {
+        match self {
+            Self::Empty => BLACK,
+            Self::Wall => DIM_GRAY,
+            Self::Visited => GOLD,
+            Self::Match => LIME,
+            Self::Marker => ORANGE_RED,
+        }
+    }
+}
+
+/// One image of an exported visualisation, with a [`Cell`] per grid position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    /// Characters to show in place of the cells, like the letters of a word search. Only the
+    /// terminal draws them, as images are too small to read.
+    pub text: Option<Grid<char>>,
+}
+
+impl Frame {
+    pub const fn new(cells: Grid<Cell>) -> Self {
+        Self { cells, text: None }
+    }
+}
+
+impl Index<IVec2> for Frame {
+    type Output = Cell;
+
+    fn index(&self, pos: IVec2) -> &Cell {
+        &self.cells[pos]
+    }
+}
+
+impl IndexMut<IVec2> for Frame {
+    fn index_mut(&mut self, pos: IVec2) -> &mut Cell {
+        &mut self.cells[pos]
+    }
+}
+
+/// An empty frame covering `bounds`, which like [`Grid::bounds`] includes the max corner.
+pub fn blank(bounds: IRect) -> Frame {
+    let size = bounds.size() + IVec2::ONE;
+    let (width, height) = (size.x as usize, size.y as usize);
+    Frame::new(Grid::new(width, height, vec![Cell::Empty; width * height]))
+}
+
+/// The steps of a `len` step timeline to draw as at most `frames` frames, spread evenly and
+/// always ending on the last.
+pub fn sample(len: usize, frames: usize) -> impl Iterator<Item = usize> {
+    let frames = frames.clamp(1, len + 1);
+    (0..frames).map(move |i| {
+        if frames == 1 {
+            len
+        } else {
+            i * len / (frames - 1)
+        }
+    })
+}
+
+/// The systems that draw each day's frames for exporting, given the most frames to draw. Added
+/// with [`crate::days::AddDay::add_export`].
+#[derive(Resource, Default, Debug)]
+pub struct Exporters(pub HashMap<u8, SystemId<In<usize>, Vec<Frame>>>);
+
+impl Exporters {
+    pub fn get(&self, day: u8) -> Option<SystemId<In<usize>, Vec<Frame>>> {
+        self.0.get(&day).copied()
+    }
+}
+
+/// Draws a frame with each cell as a `scale` pixel square.
+pub fn render(frame: &Frame, scale: u32) -> RgbaImage {
+    let scale = scale.max(1);
+    RgbaImage::from_fn(
+        frame.cells.width() as u32 * scale,
+        frame.cells.height() as u32 * scale,
+        |x, y| {
+            let pos = IVec2::new((x / scale) as i32, (y / scale) as i32);
+            Rgba(frame[pos].color().to_u8_array())
+        },
+    )
+}
+
+/// Writes frames to `out`, as an animated GIF or as PNGs depending on its extension. More than one
+/// frame written as PNG gives a numbered file per frame next to `out`. Returns the files written.
+pub fn write(frames: &[Frame], out: &Path, scale: u32) -> Result<Vec<PathBuf>, ExportError> {
+    if frames.is_empty() {
+        return Err(ExportError::NoFrames);
+    }
+
+    match out.extension().and_then(|ext| ext.to_str()) {
+        Some("gif") => {
+            let mut encoder = GifEncoder::new(BufWriter::new(File::create(out)?));
+            encoder.set_repeat(Repeat::Infinite)?;
+            for frame in frames {
+                encoder.encode_frame(image::Frame::from_parts(
+                    render(frame, scale),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(FRAME_DELAY_MS, 1),
+                ))?;
+            }
+            Ok(vec![out.into()])
+        }
+        Some("png") if frames.len() == 1 => {
+            render(&frames[0], scale).save(out)?;
+            Ok(vec![out.into()])
+        }
+        Some("png") => {
+            let stem = out.file_stem().unwrap_or_default().to_string_lossy();
+            let digits = frames.len().to_string().len();
+            frames
+                .iter()
+                .enumerate()
+                .map(|(i, frame)| {
+                    let path = out.with_file_name(format!("{stem}-{i:0digits$}.png"));
+                    render(frame, scale).save(&path)?;
+                    Ok(path)
+                })
+                .collect()
+        }
+        _ => Err(ExportError::Format(out.into())),
+    }
+}
+
+/// Possible errors when exporting a visualisation.
+#[derive(Debug, Error, Diagnostic)]
+pub enum ExportError {
+    #[error("There were no frames to export")]
+    NoFrames,
+
+    #[error("Can't tell what format to export {} as", .0.display())]
+    #[diagnostic(
+        code(aoc::export::format),
+        help("end the file name with .gif for an animation, or .png for an image per frame")
+    )]
+    Format(PathBuf),
+
+    #[error("Could not encode the frames: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Could not write the frames: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_sample_ends_on_last_step() {
+        assert_eq!(sample(10, 3).collect::<Vec<_>>(), [0, 5, 10]);
+        assert_eq!(sample(2, 100).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(sample(7, 1).collect::<Vec<_>>(), [7]);
+    }
+
+    #[test]
+    fn test_write_gif_and_pngs() -> Result<(), ExportError> {
+        let mut frame = blank(IRect::new(0, 0, 2, 1));
+        frame[IVec2::new(2, 1)] = Cell::Marker;
+        let image = render(&frame, 4);
+        assert_eq!(image.dimensions(), (12, 8));
+        assert_eq!(image.get_pixel(0, 0).0, BLACK.to_u8_array());
+        assert_eq!(image.get_pixel(11, 7).0, ORANGE_RED.to_u8_array());
+
+        let dir = TempDir::new("export");
+        let frames = [frame.clone(), blank(IRect::new(0, 0, 2, 1))];
+
+        let gif = write(&frames, &dir.join("walk.gif"), 4)?;
+        assert!(std::fs::read(&gif[0])?.starts_with(b"GIF89a"));
+        let pngs = write(&frames, &dir.join("walk.png"), 4)?;
+        assert_eq!(pngs, [dir.join("walk-0.png"), dir.join("walk-1.png")]);
+        assert!(matches!(
+            write(&frames, &dir.join("walk.bmp"), 4),
+            Err(ExportError::Format(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use bevy::{color::palettes::css::RED, ecs::system::SystemParam, prelude::*, utils::HashMap};
+use miette::{
+    Diagnostic, GraphicalReportHandler, GraphicalTheme, NamedSource, Report, SourceSpan,
+    ThemeCharacters, ThemeStyles,
+};
+use thiserror::Error;
+
+use crate::days::DayRoot;
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<PuzzleError>()
+        .init_resource::<FailedDays>()
+        .add_systems(Update, (log_errors, show_errors));
+}
+
+/// Sent when a day can't be solved, usually because its input is malformed.
+#[derive(Event, Clone, Debug)]
+pub struct PuzzleError {
+    pub day: u8,
+    pub report: Arc<Report>,
+}
+
+/// Days that have failed since they were last entered, with what went wrong. Their remaining
+/// phases are skipped.
+#[derive(Resource, Default, Debug)]
+pub struct FailedDays(HashMap<u8, Arc<Report>>);
+
+impl FailedDays {
+    pub fn get(&self, day: u8) -> Option<&Arc<Report>> {
+        self.0.get(&day)
+    }
+
+    pub fn clear(&mut self, day: u8) {
+        self.0.remove(&day);
+    }
+}
+
+/// Reports [`PuzzleError`]s and marks the day as failed.
+#[derive(SystemParam)]
+pub struct Failures<'w> {
+    events: EventWriter<'w, PuzzleError>,
+    failed: ResMut<'w, FailedDays>,
+}
+
+impl Failures<'_> {
+    pub fn report(&mut self, day: u8, report: impl Into<Report>) {
+        let report = Arc::new(report.into());
+        // Keep the first error, which the others usually follow on from.
+        self.failed.0.entry(day).or_insert_with(|| report.clone());
+        self.events.send(PuzzleError { day, report });
+    }
+}
+
+/// A problem found in a day's puzzle input, labelled with where in the input it was found.
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+#[diagnostic(code(aoc::input))]
+pub struct InputError {
+    message: String,
+    #[source_code]
+    input: NamedSource<String>,
+    #[label("{label}")]
+    span: SourceSpan,
+    label: String,
+}
+
+impl InputError {
+    pub fn new(
+        day: u8,
+        input: &str,
+        span: impl Into<SourceSpan>,
+        message: impl Into<String>,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            input: NamedSource::new(format!("day {day} input"), input.to_owned()),
+            span: span.into(),
+            label: label.into(),
+        }
+    }
+}
+
+/// The span of the zero-based `line` in `input`, not counting its line ending. Points just past
+/// the end of the input if there aren't that many lines.
+pub fn line_span(input: &str, line: usize) -> SourceSpan {
+    let mut offset = 0;
+    for (i, l) in input.split_inclusive('\n').enumerate() {
+        if i == line {
+            return (offset, l.trim_end_matches(['\r', '\n']).len()).into();
+        }
+        offset += l.len();
+    }
+    (input.len(), 0).into()
+}
+
+/// The node covering a day's UI once it has failed.
+#[derive(Component, Debug)]
+pub struct ErrorOverlay;
+
+fn log_errors(mut errors: EventReader<PuzzleError>) {
+    for error in errors.read() {
+        error!("Day {} failed: {:?}", error.day, error.report);
+    }
+}
+
+fn show_errors(
+    mut errors: EventReader<PuzzleError>,
+    mut commands: Commands,
+    roots: Query<(Entity, &DayRoot)>,
+) {
+    // Box-drawing characters are missing from the default font, and text nodes don't understand
+    // ANSI colours, so stick to plain ASCII.
+    let handler = GraphicalReportHandler::new_themed(GraphicalTheme {
+        characters: ThemeCharacters::ascii(),
+        styles: ThemeStyles::none(),
+    });
+    for error in errors.read() {
+        let mut rendered = String::new();
+        if handler
+            .render_report(&mut rendered, &**error.report)
+            .is_err()
+        {
+            rendered = error.report.to_string();
+        }
+
+        for (entity, root) in &roots {
+            if root.0 != error.day {
+                continue;
+            }
+            commands.entity(entity).with_children(|p| {
+                p.spawn((
+                    Name::new("Error Overlay"),
+                    ErrorOverlay,
+                    BackgroundColor(Color::BLACK.with_alpha(0.9)),
+                    BorderColor(RED.into()),
+                    Node {
+                        border: UiRect::all(Val::Px(2.)),
+                        height: Val::Percent(100.),
+                        left: Val::Px(0.),
+                        padding: UiRect::all(Val::Px(20.)),
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(0.),
+                        width: Val::Percent(100.),
+                        ..default()
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn((
+                        Text::new(rendered.clone()),
+                        TextFont {
+                            font_size: 14.,
+                            ..default()
+                        },
+                    ));
+                });
+            });
+        }
+    }
+}
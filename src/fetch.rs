@@ -0,0 +1,197 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::io::file::FileAssetReader,
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use miette::{Diagnostic, Report};
+use thiserror::Error;
+
+use crate::{
+    client::{Client, ClientError, NoSessionError},
+    days::Days,
+    loading::InputSource,
+    AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, finish_fetches);
+}
+
+/// Downloads a single day's input without opening a window.
+pub fn run(day: u8, base_url: Option<String>) -> AppExit {
+    let result = Fetcher::from_env().and_then(|mut fetcher| {
+        if let Some(base_url) = base_url {
+            fetcher.client.base_url = base_url;
+        }
+        fetcher.fetch(day)
+    });
+    match result {
+        Ok(path) => {
+            println!("Saved the day {day} input to {}", path.display());
+            AppExit::Success
+        }
+        Err(e) => {
+            eprintln!("{:?}", Report::new(e));
+            AppExit::error()
+        }
+    }
+}
+
+/// Downloads puzzle inputs and caches them in the assets directory, where they are loaded from.
+#[derive(Clone)]
+pub struct Fetcher {
+    pub client: Client,
+    /// The assets directory the inputs are cached in.
+    pub assets: PathBuf,
+}
+
+impl Fetcher {
+    /// Caches inputs in the assets directory, using the account from [`Client::from_env`].
+    pub fn from_env() -> Result<Self, FetchError> {
+        Ok(Self {
+            client: Client::from_env()?,
+            assets: FileAssetReader::new("assets").root_path().clone(),
+        })
+    }
+
+    /// Where a day's input is cached.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.assets.join(InputSource::Real.path(day))
+    }
+
+    /// Downloads a day's input, returning where it was saved. Days that are already cached are
+    /// never downloaded again.
+    pub fn fetch(&self, day: u8) -> Result<PathBuf, FetchError> {
+        let path = self.path(day);
+        if path.exists() {
+            return Err(FetchError::Cached(path));
+        }
+
+        let input = self.client.get(&format!("day/{day}/input"))?;
+        write(&path, &input)?;
+        Ok(path)
+    }
+}
+
+// Only create the file once the whole input has arrived, so a failed download isn't mistaken for
+// a cached one.
+fn write(path: &Path, input: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, input)
+}
+
+/// Possible errors when downloading a puzzle input.
+#[derive(Debug, Error, Diagnostic)]
+pub enum FetchError {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    NoSession(#[from] NoSessionError),
+
+    #[error("{} is already cached", .0.display())]
+    #[diagnostic(code(aoc::fetch::cached), help("delete it first to download it again"))]
+    Cached(PathBuf),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Client(#[from] ClientError),
+
+    #[error("Could not save the input: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A download started from the menu.
+#[derive(Component)]
+struct FetchTask {
+    day: u8,
+    task: Task<Result<PathBuf, FetchError>>,
+}
+
+/// Downloads the input for every registered day that doesn't have one cached yet, in the
+/// background.
+pub fn fetch_missing(mut commands: Commands, days: Res<Days>) {
+    let fetcher = match Fetcher::from_env() {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            error!("{:?}", Report::new(e));
+            return;
+        }
+    };
+
+    let pool = IoTaskPool::get();
+    for day in days.iter().map(|day| day.number) {
+        if fetcher.path(day).exists() {
+            continue;
+        }
+        let fetcher = fetcher.clone();
+        commands.spawn((
+            Name::new(format!("Fetch Day {day}")),
+            FetchTask {
+                day,
+                task: pool.spawn(async move { fetcher.fetch(day) }),
+            },
+        ));
+    }
+}
+
+// Once every download has finished, reloads the inputs so the new ones can be solved. Reloading
+// leaves the current day, so it waits until the app is back at the menu.
+fn finish_fetches(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut FetchTask)>,
+    mut fetched: Local<bool>,
+    state: Res<State<AoCState>>,
+    mut next_state: ResMut<NextState<AoCState>>,
+) {
+    let mut remaining = 0;
+    for (entity, mut fetch) in &mut tasks {
+        let Some(result) = block_on(future::poll_once(&mut fetch.task)) else {
+            remaining += 1;
+            continue;
+        };
+        commands.entity(entity).despawn();
+        match result {
+            Ok(path) => {
+                info!("Saved the day {} input to {}", fetch.day, path.display());
+                *fetched = true;
+            }
+            Err(e) => error!("Could not fetch day {}: {:?}", fetch.day, Report::new(e)),
+        }
+    }
+
+    if remaining == 0 && *fetched && *state.get() == AoCState::Menu {
+        *fetched = false;
+        next_state.set(AoCState::Loading);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::mock, temp_dir::TempDir};
+
+    #[test]
+    fn test_fetch_caches_input() {
+        let (client, server) = mock::serve_once("1 2\n3 4\n");
+        let assets = TempDir::new("fetch");
+        let fetcher = Fetcher {
+            client,
+            assets: assets.to_path_buf(),
+        };
+
+        let path = fetcher.fetch(1).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2\n3 4\n");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2024/day/1/input "));
+        assert!(request.contains("session=secret"));
+
+        // The stand-in server is gone, so this would fail if it tried to download again.
+        assert!(matches!(fetcher.fetch(1), Err(FetchError::Cached(_))));
+    }
+}
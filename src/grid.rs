@@ -0,0 +1,305 @@
+use std::{
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use bevy::prelude::*;
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+/// Offsets to the neighbours above, right, below and left of a cell.
+pub const ORTHOGONAL: [IVec2; 4] = [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
+
+/// Offsets to all eight neighbours of a cell, clockwise from above.
+pub const ALL_DIRECTIONS: [IVec2; 8] = [
+    IVec2::NEG_Y,
+    IVec2::new(1, -1),
+    IVec2::X,
+    IVec2::ONE,
+    IVec2::Y,
+    IVec2::new(-1, 1),
+    IVec2::NEG_X,
+    IVec2::NEG_ONE,
+];
+
+/// A rectangular grid of cells. Positions are `(x, y)` with `x` running along a row and `y`
+/// running down the rows, starting from `(0, 0)` in the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// Written out rather than derived, which would need every cell type to have a default too.
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: vec![],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells, row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "grid cells don't fill the grid"
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every position inside the grid. Like [`IRect::contains`], the max corner is included.
+    pub const fn bounds(&self) -> IRect {
+        IRect {
+            min: IVec2::ZERO,
+            max: IVec2::new(self.width as i32 - 1, self.height as i32 - 1),
+        }
+    }
+
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.bounds().contains(pos)
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.index_of(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let index = self.index_of(pos);
+        Some(&mut self.cells[index])
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.pos_of(i), cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero chunk size, which an empty grid would otherwise ask for.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells above, right, below and left of `pos` that are inside the grid.
+    // No day walks between orthogonal neighbours yet, only the tests.
+    #[allow(dead_code)]
+    pub fn neighbours4(&self, pos: IVec2) -> impl Iterator<Item = (IVec2, &T)> {
+        self.neighbours(pos, &ORTHOGONAL)
+    }
+
+    /// The cells surrounding `pos`, including diagonals, that are inside the grid, clockwise from
+    /// above.
+    pub fn neighbours8(&self, pos: IVec2) -> impl Iterator<Item = (IVec2, &T)> {
+        self.neighbours(pos, &ALL_DIRECTIONS)
+    }
+
+    /// Converts each cell, e.g. from the characters of a puzzle input into something typed.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        pos: IVec2,
+        offsets: &'a [IVec2],
+    ) -> impl Iterator<Item = (IVec2, &'a T)> {
+        offsets.iter().filter_map(move |offset| {
+            let neighbour = pos + *offset;
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    const fn index_of(&self, pos: IVec2) -> usize {
+        pos.y as usize * self.width + pos.x as usize
+    }
+
+    const fn pos_of(&self, index: usize) -> IVec2 {
+        IVec2::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// The position of the first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<IVec2> {
+        self.find_all(value).next()
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = IVec2> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: IVec2) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: IVec2) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+/// Returned when the rows of a grid aren't all the same width.
+#[derive(Debug, Error, Diagnostic)]
+#[error("Row {row} is {len} cells wide, but the first row is {width}")]
+#[diagnostic(code(grid::ragged))]
+pub struct RaggedGridError {
+    #[source_code]
+    input: String,
+    #[label("this row")]
+    span: SourceSpan,
+    row: usize,
+    len: usize,
+    width: usize,
+}
+
+// One cell per character, one row per line. Blank lines at the end are ignored.
+impl FromStr for Grid<char> {
+    type Err = RaggedGridError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        let mut offset = 0;
+        for line in input.trim_end_matches(['\r', '\n']).split_inclusive('\n') {
+            let row = line.trim_end_matches(['\r', '\n']);
+            let len = row.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(RaggedGridError {
+                        input: input.to_owned(),
+                        span: (offset, row.len()).into(),
+                        row: height + 1,
+                        len,
+                        width,
+                    })
+                }
+                Some(_) => {}
+            }
+            cells.extend(row.chars());
+            height += 1;
+            offset += line.len();
+        }
+        Ok(Self::new(width.unwrap_or_default(), height, cells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_index() -> Result<(), RaggedGridError> {
+        let grid: Grid<char> = "ab\ncd\n\n".parse()?;
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.bounds(), IRect::new(0, 0, 1, 1));
+        assert_eq!(grid[IVec2::new(1, 0)], 'b');
+        assert_eq!(grid.get(IVec2::new(0, 1)), Some(&'c'));
+        assert_eq!(grid.get(IVec2::new(2, 0)), None);
+        assert!(!grid.contains(IVec2::NEG_ONE));
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b'].as_slice(), ['c', 'd'].as_slice()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_and_map() -> Result<(), RaggedGridError> {
+        let mut grid: Grid<char> = "#.\r\n.#".parse()?;
+        assert_eq!(grid.find(&'#'), Some(IVec2::ZERO));
+        assert_eq!(
+            grid.find_all(&'#').collect::<Vec<_>>(),
+            [IVec2::ZERO, IVec2::ONE]
+        );
+        assert_eq!(grid.find(&'x'), None);
+
+        grid[IVec2::X] = '#';
+        let walls = grid.map(|cell| *cell == '#');
+        assert_eq!(walls.iter().filter(|(_, wall)| **wall).count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> Result<(), RaggedGridError> {
+        let grid: Grid<char> = "abc\ndef\nghi".parse()?;
+        let letters = |cells: Vec<(IVec2, &char)>| -> String {
+            cells.into_iter().map(|(_, cell)| *cell).collect()
+        };
+
+        // In the middle every neighbour is there, in clockwise order from above.
+        assert_eq!(letters(grid.neighbours4(IVec2::ONE).collect()), "bfhd");
+        assert_eq!(letters(grid.neighbours8(IVec2::ONE).collect()), "bcfihgda");
+        // Along an edge and in a corner, the ones off the grid are left out.
+        assert_eq!(letters(grid.neighbours4(IVec2::new(1, 0)).collect()), "cea");
+        assert_eq!(
+            letters(grid.neighbours8(IVec2::new(1, 0)).collect()),
+            "cfeda"
+        );
+        assert_eq!(letters(grid.neighbours4(IVec2::new(2, 2)).collect()), "fh");
+        assert_eq!(letters(grid.neighbours8(IVec2::new(2, 2)).collect()), "fhe");
+        // The positions come with the cells.
+        assert_eq!(
+            grid.neighbours4(IVec2::ZERO).collect::<Vec<_>>(),
+            [(IVec2::X, &'b'), (IVec2::Y, &'d')]
+        );
+        // A position outside the grid can still border it.
+        assert_eq!(
+            letters(grid.neighbours8(IVec2::new(-1, 1)).collect()),
+            "adg"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let error = "abc\nab\nabc".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((error.row, error.len, error.width), (2, 2, 3));
+        // The label covers the short row, without its newline.
+        assert_eq!(error.span, (4, 2).into());
+    }
+
+    #[test]
+    fn test_empty_grid() -> Result<(), RaggedGridError> {
+        let grid: Grid<char> = "".parse()?;
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.rows().count(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! Drives the real app through each day with the sample inputs, so the wiring between loading,
+//! states and each day's systems is tested as well as the solvers themselves.
+
+use std::time::{Duration, Instant};
+
+use bevy::{asset::ron, prelude::*};
+
+use crate::{
+    answer::{Part, PuzzleAnswer},
+    days::{DayPhase, DayRoot, Days},
+    expected_answers_asset::ExpectedAnswersAsset,
+    failure::PuzzleError,
+    headless::{self, Report},
+    loading::{InputPaths, InputSource},
+    timing::{Stage, StageTimed},
+    AoCState,
+};
+
+// The sample inputs double as the fixtures, with their answers alongside.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+
+// The samples are solved within a few frames of entering a day, a phase at a time, or a moment
+// later for parts solved in the background, so anything slower is stuck.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A windowless app that has loaded the sample inputs and is waiting at the menu.
+pub struct Harness {
+    app: App,
+}
+
+impl Harness {
+    pub fn start() -> Self {
+        let harness = Self::start_with(InputPaths::default());
+        match harness.state() {
+            AoCState::Menu => harness,
+            state => panic!("Expected the sample inputs to load, got {state:?}"),
+        }
+    }
+
+    /// Like [`Self::start`], but loads some days' inputs from `paths` instead. Stops once loading
+    /// is over, at the menu or at the list of inputs that failed to load.
+    pub fn start_with(paths: InputPaths) -> Self {
+        let mut app = headless::solver(
+            AssetPlugin {
+                file_path: FIXTURES.into(),
+                ..default()
+            },
+            InputSource::Sample,
+            paths,
+        );
+        app.init_resource::<Report>().add_systems(Update, record);
+        headless::finish(&mut app);
+
+        let mut harness = Self { app };
+        harness
+            .update_until(|world| *world.resource::<State<AoCState>>().get() != AoCState::Loading);
+        harness
+    }
+
+    pub fn state(&self) -> AoCState {
+        *self.app.world().resource::<State<AoCState>>().get()
+    }
+
+    /// Every registered day, in order.
+    pub fn days(&self) -> Vec<u8> {
+        self.app
+            .world()
+            .resource::<Days>()
+            .iter()
+            .map(|day| day.number)
+            .collect()
+    }
+
+    /// Opens `day` as the menu would, and returns what it reported on the way in.
+    pub fn open(&mut self, day: u8) -> Report {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<AoCState>>()
+            .set(AoCState::Day(day));
+        self.update_until(|world| {
+            // Going from one day to another, the old day's phase lingers until the new one starts
+            // parsing, which it always times.
+            let parsed = world
+                .resource::<Report>()
+                .stages
+                .iter()
+                .any(|timed| timed.day == day && timed.stage == Stage::Process);
+            parsed
+                && world
+                    .get_resource::<State<DayPhase>>()
+                    .is_some_and(|phase| phase.is_finished())
+        });
+        std::mem::take(&mut *self.app.world_mut().resource_mut::<Report>())
+    }
+
+    /// Goes back to the menu, leaving whichever day was open, or the list of broken inputs.
+    pub fn leave(&mut self) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<AoCState>>()
+            .set(AoCState::Menu);
+        self.update_until(|world| *world.resource::<State<AoCState>>().get() == AoCState::Menu);
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    fn update_until(&mut self, done: impl Fn(&World) -> bool) {
+        let started = Instant::now();
+        while started.elapsed() < TIMEOUT {
+            self.app.update();
+            if done(self.app.world()) {
+                return;
+            }
+        }
+        let state = self.app.world().resource::<State<AoCState>>().get();
+        panic!("Still waiting after {TIMEOUT:?}, in {state:?}");
+    }
+}
+
+fn record(
+    mut answers: EventReader<PuzzleAnswer>,
+    mut errors: EventReader<PuzzleError>,
+    mut timed: EventReader<StageTimed>,
+    mut report: ResMut<Report>,
+) {
+    report.answers.extend(answers.read().cloned());
+    report.errors.extend(errors.read().cloned());
+    report.stages.extend(timed.read().cloned());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::{loading::BrokenInputs, temp_dir::TempDir};
+
+    // Parts that don't report their sample answer yet. Day 9 part B isn't solved.
+    const KNOWN_GAPS: &[(u8, Part)] = &[(9, Part::B)];
+
+    #[test]
+    fn test_every_day_through_the_app() {
+        let expected: ExpectedAnswersAsset =
+            ron::from_str(include_str!("../assets/sample/answers.ron")).unwrap();
+        let mut harness = Harness::start();
+        let days = harness.days();
+        assert!(!days.is_empty());
+
+        for day in days {
+            let report = harness.open(day);
+            assert!(
+                report.errors.is_empty(),
+                "Day {day} failed: {:?}",
+                report.errors
+            );
+            for part in [Part::A, Part::B] {
+                if KNOWN_GAPS.contains(&(day, part)) {
+                    continue;
+                }
+                let answer = report
+                    .answers
+                    .iter()
+                    .find(|answer| answer.part == part)
+                    .map(|answer| answer.value.as_str());
+                assert_eq!(answer, expected.get(day, part), "Day {day} part {part:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_opening_a_day_again_gives_the_same_answers() {
+        let mut harness = Harness::start();
+        for day in harness.days() {
+            let answers = |report: Report| -> HashMap<Part, String> {
+                report
+                    .answers
+                    .into_iter()
+                    .map(|answer| (answer.part, answer.value))
+                    .collect()
+            };
+            let first = answers(harness.open(day));
+            harness.leave();
+            let second = answers(harness.open(day));
+            assert_eq!(first, second, "Day {day}");
+
+            let world = harness.world_mut();
+            let roots = world
+                .query_filtered::<(), With<DayRoot>>()
+                .iter(world)
+                .count();
+            assert_eq!(roots, 1, "Day {day} left its old entities behind");
+        }
+    }
+
+    // A missing input sends loading to the list of broken inputs, but only its own day is lost:
+    // the others can still be opened once past it.
+    #[test]
+    fn test_missing_input_leaves_the_other_days_solvable() {
+        let mut paths = InputPaths::default();
+        paths.0.insert(5, format!("{FIXTURES}/sample/missing.aoc"));
+        let mut harness = Harness::start_with(paths);
+        assert_eq!(harness.state(), AoCState::LoadingFailed);
+        let broken: Vec<u8> = harness
+            .world_mut()
+            .resource::<BrokenInputs>()
+            .iter()
+            .map(|(day, _)| day)
+            .collect();
+        assert_eq!(broken, [5]);
+
+        // As the "Continue to menu" button does.
+        harness.leave();
+        let expected: ExpectedAnswersAsset =
+            ron::from_str(include_str!("../assets/sample/answers.ron")).unwrap();
+        let report = harness.open(1);
+        assert!(
+            report.errors.is_empty(),
+            "Day 1 failed: {:?}",
+            report.errors
+        );
+        assert_eq!(report.answers.len(), 2);
+        for answer in report.answers {
+            assert_eq!(Some(answer.value.as_str()), expected.get(1, answer.part));
+        }
+    }
+
+    // Day 5 reads its rules in pairs, so a rule with one number fails the day as it's processed.
+    #[test]
+    fn test_malformed_input_fails_the_day() {
+        let dir = TempDir::new("harness");
+        let input = dir.join("5.aoc");
+        std::fs::write(&input, "47|53\n97\n\n75,47,61\n").unwrap();
+        let mut paths = InputPaths::default();
+        paths.0.insert(5, input.to_string_lossy().into_owned());
+
+        let mut harness = Harness::start_with(paths);
+        let report = harness.open(5);
+        let phase = *harness.world_mut().resource::<State<DayPhase>>().get();
+        assert_eq!(phase, DayPhase::Failed);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.answers.is_empty());
+    }
+}
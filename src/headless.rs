@@ -0,0 +1,525 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    io::{self, IsTerminal},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bevy::{app::PluginsState, prelude::*, state::app::StatesPlugin};
+use miette::miette;
+
+use crate::{
+    answer::{self, Part, PuzzleAnswer},
+    days::{self, DayPhase, Days},
+    export::{self, Exporters, Frame},
+    failure::{self, PuzzleError},
+    loading::{self, BrokenInputs, InputPaths, InputSource},
+    tasks::{self, Progress},
+    terminal,
+    timing::{self, Stage, StageTimed},
+    AoCState,
+};
+
+// Solving a day gives up after this long, so a stuck solver can't keep a thread busy for good.
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The day the headless app was asked to solve.
+#[derive(Resource, Debug)]
+struct Target(u8);
+
+/// Everything a headless run of a day produced.
+#[derive(Resource, Default, Debug)]
+pub struct Report {
+    pub answers: Vec<PuzzleAnswer>,
+    pub errors: Vec<PuzzleError>,
+    pub stages: Vec<StageTimed>,
+}
+
+/// Solves a single day without a window, using the same loaders and systems as the full app.
+pub fn run(day: u8, part: Option<Part>, input: Option<PathBuf>, source: InputSource) -> AppExit {
+    let paths = match &input {
+        Some(input) => match input_paths(day, input) {
+            Ok(paths) => paths,
+            Err(e) => {
+                eprintln!("Could not read {}: {e}", input.display());
+                return AppExit::error();
+            }
+        },
+        None => InputPaths::default(),
+    };
+
+    let Some(report) = solve(day, source, paths) else {
+        eprintln!("No solution for day {day}");
+        return AppExit::error();
+    };
+
+    for answer in report.answers {
+        if part.is_none_or(|part| part == answer.part) {
+            println!("{:?}: {}", answer.part, answer.value);
+        }
+    }
+    if report.errors.is_empty() {
+        AppExit::Success
+    } else {
+        for error in report.errors {
+            eprintln!("{:?}", error.report);
+        }
+        AppExit::error()
+    }
+}
+
+/// Solves a day with its real input and returns one part's answer, printing any errors instead.
+pub fn answer(day: u8, part: Part) -> Option<String> {
+    let report = solve(day, InputSource::Real, InputPaths::default())?;
+    for error in &report.errors {
+        eprintln!("{:?}", error.report);
+    }
+    report
+        .answers
+        .into_iter()
+        .find(|answer| answer.part == part)
+        .map(|answer| answer.value)
+}
+
+/// Solves each day `runs` times, printing the fastest, median and slowest time for each stage and
+/// writing them to `out` as CSV.
+pub fn bench(day: Option<u8>, runs: u32, out: &Path, source: InputSource) -> AppExit {
+    let days: Vec<u8> = day.map_or_else(
+        || {
+            app(source, InputPaths::default())
+                .world()
+                .resource::<Days>()
+                .iter()
+                .map(|day| day.number)
+                .collect()
+        },
+        |day| vec![day],
+    );
+
+    let mut csv = String::from("day,stage,runs,min_ns,median_ns,max_ns\n");
+    println!(
+        "{:>3} {:<8} {:>10} {:>10} {:>10}",
+        "day", "stage", "min", "median", "max"
+    );
+    for day in days {
+        let mut timings: BTreeMap<Stage, Vec<Duration>> = BTreeMap::new();
+        for _ in 0..runs {
+            let Some(report) = solve(day, source, InputPaths::default()) else {
+                eprintln!("No solution for day {day}");
+                return AppExit::error();
+            };
+            if let Some(error) = report.errors.first() {
+                eprintln!("{:?}", error.report);
+                return AppExit::error();
+            }
+            for timed in report.stages {
+                timings.entry(timed.stage).or_default().push(timed.elapsed);
+            }
+        }
+
+        for (stage, mut durations) in timings {
+            durations.sort();
+            let min = durations[0];
+            let median = durations[durations.len() / 2];
+            let max = durations[durations.len() - 1];
+            println!(
+                "{day:>3} {stage:<8} {:>10} {:>10} {:>10}",
+                format!("{min:.2?}"),
+                format!("{median:.2?}"),
+                format!("{max:.2?}"),
+            );
+            let _ = writeln!(
+                csv,
+                "{day},{stage},{},{},{},{}",
+                durations.len(),
+                min.as_nanos(),
+                median.as_nanos(),
+                max.as_nanos()
+            );
+        }
+    }
+
+    if let Err(e) = std::fs::write(out, csv) {
+        eprintln!("Could not write {}: {e}", out.display());
+        return AppExit::error();
+    }
+    AppExit::Success
+}
+
+/// Solves every `N-<name>.aoc` input in `dir`, printing a table of their answers and timings.
+/// Inputs that panic, fail or leave a part unanswered are flagged, as they usually mean a solver
+/// assumes something about the input that only holds for some people's.
+pub fn batch(dir: &Path, day: Option<u8>) -> AppExit {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Could not read {}: {e}", dir.display());
+            return AppExit::error();
+        }
+    };
+    let mut inputs: Vec<(u8, String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter_map(|path| {
+            let (number, name) = batch_input(&path)?;
+            Some((number, name, path))
+        })
+        .filter(|(number, ..)| day.is_none_or(|day| day == *number))
+        .collect();
+    inputs.sort();
+    if inputs.is_empty() {
+        eprintln!("No `N-<name>.aoc` inputs in {}", dir.display());
+        return AppExit::error();
+    }
+
+    println!(
+        "{:>3} {:<16} {:>16} {:>16} {:>10} status",
+        "day", "input", "A", "B", "time"
+    );
+    let mut flagged = 0;
+    for (day, name, path) in inputs {
+        let (report, status) = solve_batch_input(day, &path);
+        let answer = |part| {
+            report
+                .iter()
+                .flat_map(|report| &report.answers)
+                .find(|answer| answer.part == part)
+                .map_or("-", |answer| answer.value.as_str())
+        };
+        let elapsed: Duration = report
+            .iter()
+            .flat_map(|report| &report.stages)
+            .map(|timed| timed.elapsed)
+            .sum();
+        println!(
+            "{day:>3} {name:<16} {:>16} {:>16} {:>10} {}",
+            answer(Part::A),
+            answer(Part::B),
+            format!("{elapsed:.2?}"),
+            status.as_deref().unwrap_or("ok"),
+        );
+        flagged += usize::from(status.is_some());
+    }
+
+    if flagged == 0 {
+        AppExit::Success
+    } else {
+        eprintln!("{flagged} inputs flagged");
+        AppExit::error()
+    }
+}
+
+/// The day and name of a batch input, from a file name like `6-alice.aoc`.
+fn batch_input(path: &Path) -> Option<(u8, String)> {
+    if path.extension()? != "aoc" {
+        return None;
+    }
+    let (day, name) = path.file_stem()?.to_str()?.split_once('-')?;
+    Some((day.parse().ok()?, name.to_string()))
+}
+
+// Solves one batch input, and says what's wrong with how it went, if anything.
+fn solve_batch_input(day: u8, path: &Path) -> (Option<Report>, Option<String>) {
+    let paths = match input_paths(day, path) {
+        Ok(paths) => paths,
+        Err(e) => return (None, Some(format!("unreadable: {e}"))),
+    };
+
+    let report =
+        match panic::catch_unwind(AssertUnwindSafe(|| solve(day, InputSource::Real, paths))) {
+            Ok(Some(report)) => report,
+            Ok(None) => return (None, Some(format!("no solution for day {day}"))),
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("unknown cause");
+                return (None, Some(format!("panicked: {message}")));
+            }
+        };
+
+    let status = report.errors.first().map_or_else(
+        || {
+            let missing: Vec<_> = [Part::A, Part::B]
+                .into_iter()
+                .filter(|part| report.answers.iter().all(|answer| answer.part != *part))
+                .map(|part| format!("{part:?}"))
+                .collect();
+            (!missing.is_empty()).then(|| format!("no answer for {}", missing.join(" or ")))
+        },
+        |error| Some(format!("failed: {}", error.report)),
+    );
+    (Some(report), status)
+}
+
+// Loads `day`'s input from `input` instead. Asset paths are relative to the assets directory, so
+// the loader is handed an absolute path.
+fn input_paths(day: u8, input: &Path) -> io::Result<InputPaths> {
+    let mut paths = InputPaths::default();
+    paths
+        .0
+        .insert(day, input.canonicalize()?.to_string_lossy().into_owned());
+    Ok(paths)
+}
+
+fn app(source: InputSource, paths: InputPaths) -> App {
+    // Each app solves a day once and is thrown away, so there's no point watching for changes.
+    let assets = AssetPlugin {
+        watch_for_changes_override: Some(false),
+        ..default()
+    };
+    let mut app = solver(assets, source, paths);
+    app.init_resource::<Report>()
+        .add_systems(OnEnter(AoCState::Menu), skip_menu)
+        .add_systems(OnEnter(AoCState::LoadingFailed), skip_broken_inputs);
+    app
+}
+
+/// Builds an app with everything needed to load inputs and solve days, but no window. It starts
+/// out loading the inputs, and stops at the menu like the full app.
+pub fn solver(assets: AssetPlugin, source: InputSource, paths: InputPaths) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        assets,
+        StatesPlugin,
+        answer::plugin,
+        failure::plugin,
+        timing::plugin,
+        loading::plugin,
+        tasks::plugin,
+        days::plugin,
+    ))
+    // Some days load fonts for their visualisations, even though nothing is drawn here.
+    .init_asset::<Font>()
+    .insert_resource(paths)
+    .insert_resource(source)
+    .init_state::<AoCState>()
+    .enable_state_scoped_entities::<AoCState>();
+    app
+}
+
+/// Finishes building an app that is driven by hand rather than with `App::run`, so its world is
+/// still around afterwards.
+pub fn finish(app: &mut App) {
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+}
+
+/// Solves a day with its input from `source`, then draws it as frames and writes them to `out`.
+pub fn export(day: u8, out: &Path, frames: usize, scale: u32, source: InputSource) -> AppExit {
+    let Some(drawn) = draw(day, frames, source) else {
+        return AppExit::error();
+    };
+    match export::write(&drawn, out, scale) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
+            }
+            AppExit::Success
+        }
+        Err(e) => {
+            eprintln!("{:?}", miette::Report::new(e));
+            AppExit::error()
+        }
+    }
+}
+
+/// Solves a day with its input from `source`, then plays its visualisation in the terminal. When
+/// stdout isn't a terminal, only the last frame is printed, without colours.
+pub fn show(day: u8, frames: usize, fps: u32, source: InputSource) -> AppExit {
+    let Some(drawn) = draw(day, frames, source) else {
+        return AppExit::error();
+    };
+    let mut stdout = std::io::stdout().lock();
+    if !stdout.is_terminal() {
+        if let Some(last) = drawn.last() {
+            print!("{}", terminal::render(last, false));
+        }
+        return AppExit::Success;
+    }
+    match terminal::play(&drawn, fps, &mut stdout) {
+        Ok(()) => AppExit::Success,
+        Err(e) => {
+            eprintln!("Could not draw to the terminal: {e}");
+            AppExit::error()
+        }
+    }
+}
+
+// Solves a day and draws at most `frames` frames of it, printing why if it can't.
+fn draw(day: u8, frames: usize, source: InputSource) -> Option<Vec<Frame>> {
+    let Some(mut app) = solved(day, source, InputPaths::default(), &Progress::default()) else {
+        eprintln!("No solution for day {day}");
+        return None;
+    };
+    let report = app
+        .world_mut()
+        .remove_resource::<Report>()
+        .unwrap_or_default();
+    if !report.errors.is_empty() {
+        for error in report.errors {
+            eprintln!("{:?}", error.report);
+        }
+        return None;
+    }
+
+    let Some(id) = app
+        .world()
+        .get_resource::<Exporters>()
+        .and_then(|exporters| exporters.get(day))
+    else {
+        eprintln!("Day {day} has no visualisation to draw");
+        return None;
+    };
+    match app.world_mut().run_system_with_input(id, frames) {
+        Ok(drawn) => Some(drawn),
+        Err(e) => {
+            eprintln!("Could not draw day {day}: {e}");
+            None
+        }
+    }
+}
+
+/// Solves a day in a fresh app, returning `None` if there is no solution for it.
+pub fn solve(day: u8, source: InputSource, paths: InputPaths) -> Option<Report> {
+    solve_unless_cancelled(day, source, paths, &Progress::default())
+}
+
+/// Like [`solve`], but stops early and returns `None` once `cancel` is cancelled.
+pub fn solve_unless_cancelled(
+    day: u8,
+    source: InputSource,
+    paths: InputPaths,
+    cancel: &Progress,
+) -> Option<Report> {
+    solved(day, source, paths, cancel)?
+        .world_mut()
+        .remove_resource::<Report>()
+}
+
+// Solves a day and hands back the app, so whatever the day left in the world can be looked at. A
+// day that takes longer than `SOLVE_TIMEOUT` is reported as failed.
+fn solved(day: u8, source: InputSource, paths: InputPaths, cancel: &Progress) -> Option<App> {
+    let mut app = app(source, paths);
+    app.world().resource::<Days>().get(day)?;
+
+    let state = AoCState::Day(day);
+    app.insert_resource(Target(day))
+        .add_systems(Update, record.run_if(in_state(state)));
+
+    finish(&mut app);
+    let started = Instant::now();
+    while app.should_exit().is_none() {
+        if cancel.is_cancelled() {
+            return None;
+        }
+        if started.elapsed() > SOLVE_TIMEOUT {
+            app.world_mut()
+                .resource_mut::<Report>()
+                .errors
+                .push(PuzzleError {
+                    day,
+                    report: Arc::new(miette!("Gave up after {SOLVE_TIMEOUT:?}")),
+                });
+            break;
+        }
+        app.update();
+    }
+    Some(app)
+}
+
+fn skip_menu(target: Res<Target>, mut next_state: ResMut<NextState<AoCState>>) {
+    next_state.set(AoCState::Day(target.0));
+}
+
+// Another day's broken input shouldn't stop this one being solved, but this day's should.
+fn skip_broken_inputs(
+    target: Res<Target>,
+    broken: Res<BrokenInputs>,
+    mut exit: EventWriter<AppExit>,
+    mut report: ResMut<Report>,
+    mut next_state: ResMut<NextState<AoCState>>,
+) {
+    let Some(error) = broken.get(target.0) else {
+        next_state.set(AoCState::Day(target.0));
+        return;
+    };
+    report.errors.push(PuzzleError {
+        day: target.0,
+        report: Arc::new(miette!("{error}")),
+    });
+    exit.send(AppExit::error());
+}
+
+// The day moves through a phase each frame, and is finished once it's done or has failed.
+fn record(
+    mut answers: EventReader<PuzzleAnswer>,
+    mut errors: EventReader<PuzzleError>,
+    mut exit: EventWriter<AppExit>,
+    mut report: ResMut<Report>,
+    mut timed: EventReader<StageTimed>,
+    phase: Option<Res<State<DayPhase>>>,
+) {
+    report.answers.extend(answers.read().cloned());
+    report.errors.extend(errors.read().cloned());
+    report.stages.extend(timed.read().cloned());
+    if phase.is_some_and(|phase| phase.is_finished()) {
+        exit.send(AppExit::Success);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_batch_input_names() {
+        assert_eq!(
+            batch_input(Path::new("team/6-alice.aoc")),
+            Some((6, "alice".into()))
+        );
+        // Only the first dash separates the day from the name.
+        assert_eq!(
+            batch_input(Path::new("12-bob-smith.aoc")),
+            Some((12, "bob-smith".into()))
+        );
+        assert_eq!(batch_input(Path::new("6-alice.aoc.meta")), None);
+        assert_eq!(batch_input(Path::new("alice.aoc")), None);
+        assert_eq!(batch_input(Path::new("six-alice.aoc")), None);
+    }
+
+    // Day 5's input needs its own loader settings. A batch input without a `.meta` file gets the
+    // ones the day's inputs in `assets` use, and one with a `.meta` file gets those instead.
+    #[test]
+    fn test_batch_input_with_loader_settings() {
+        let dir = TempDir::new("batch");
+        let sample = include_str!("../assets/sample/5.aoc");
+        let plain = dir.join("5-plain.aoc");
+        fs::write(&plain, sample).unwrap();
+        let semicolons = dir.join("5-semicolons.aoc");
+        fs::write(&semicolons, sample.replace(',', ";")).unwrap();
+        let meta = include_str!("../assets/sample/5.aoc.meta").replace("|,", "|;");
+        fs::write(dir.join("5-semicolons.aoc.meta"), meta).unwrap();
+
+        let results = [plain, semicolons].map(|path| solve_batch_input(5, &path));
+        for (report, status) in results {
+            assert_eq!(status, None);
+            let answers: Vec<_> = report
+                .unwrap()
+                .answers
+                .into_iter()
+                .map(|answer| (answer.part, answer.value))
+                .collect();
+            assert_eq!(answers, [(Part::A, "143".into()), (Part::B, "123".into())]);
+        }
+    }
+}
@@ -1,45 +1,231 @@
-use bevy::prelude::*;
+use std::{path::Path, sync::Arc};
+
+use bevy::{
+    asset::{AssetLoadError, LoadState},
+    color::palettes::css::RED,
+    prelude::*,
+    utils::HashMap,
+};
 use bevy_asset_loader::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    days::{Days, Solution},
     puzzle_input_asset::{PuzzleInputAsset, PuzzleInputAssetLoader},
+    puzzle_input_grid_asset::{PuzzleInputGridAsset, PuzzleInputGridAssetLoader},
     puzzle_input_lines_asset::{PuzzleInputLinesAsset, PuzzleInputLinesAssetLoader},
     puzzle_input_string_asset::{PuzzleInputStringAsset, PuzzleInputStringAssetLoader},
-    AoCState,
+    ui, AoCState,
 };
 
 pub fn plugin(app: &mut App) {
     app.init_asset::<PuzzleInputAsset>()
+        .init_asset::<PuzzleInputGridAsset>()
         .init_asset::<PuzzleInputLinesAsset>()
         .init_asset::<PuzzleInputStringAsset>()
         .init_asset_loader::<PuzzleInputAssetLoader>()
+        .init_asset_loader::<PuzzleInputGridAssetLoader>()
         .init_asset_loader::<PuzzleInputLinesAssetLoader>()
         .init_asset_loader::<PuzzleInputStringAssetLoader>()
+        .init_resource::<InputPaths>()
+        .init_resource::<InputSource>()
+        .init_resource::<BrokenInputs>()
         .add_loading_state(
             LoadingState::new(AoCState::Loading)
                 .continue_to_state(AoCState::Menu)
+                .on_failure_continue_to_state(AoCState::LoadingFailed)
                 .load_collection::<PuzzleInputs>(),
-        );
-}
-
-#[derive(AssetCollection, Resource)]
-pub struct PuzzleInputs {
-    #[asset(path = "input/1.aoc")]
-    pub one: Handle<PuzzleInputAsset>,
-    #[asset(path = "input/2.aoc")]
-    pub two: Handle<PuzzleInputAsset>,
-    #[asset(path = "input/3.aoc")]
-    pub three: Handle<PuzzleInputLinesAsset>,
-    #[asset(path = "input/4.aoc")]
-    pub four: Handle<PuzzleInputLinesAsset>,
-    #[asset(path = "input/5.aoc")]
-    pub five: Handle<PuzzleInputLinesAsset>,
-    #[asset(path = "input/6.aoc")]
-    pub six: Handle<PuzzleInputStringAsset>,
-    #[asset(path = "input/7.aoc")]
-    pub seven: Handle<PuzzleInputStringAsset>,
-    #[asset(path = "input/8.aoc")]
-    pub eight: Handle<PuzzleInputStringAsset>,
-    #[asset(path = "input/9.aoc")]
-    pub nine: Handle<PuzzleInputStringAsset>,
+        )
+        .add_systems(OnExit(AoCState::Loading), find_broken_inputs)
+        .add_systems(OnEnter(AoCState::LoadingFailed), show_broken_inputs);
+}
+
+/// Whether days are solved with the real puzzle inputs or the examples from the puzzle text.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputSource {
+    #[default]
+    Real,
+    Sample,
+}
+
+impl InputSource {
+    /// Where a day's input is found in the assets directory.
+    pub fn path(self, day: u8) -> String {
+        match self {
+            Self::Real => format!("input/{day}.aoc"),
+            Self::Sample => format!("sample/{day}.aoc"),
+        }
+    }
+
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::Real => Self::Sample,
+            Self::Sample => Self::Real,
+        }
+    }
+}
+
+/// Overrides for where a day's puzzle input is loaded from. Days without an entry load from the
+/// directory for the current [`InputSource`].
+#[derive(Resource, Default, Debug)]
+pub struct InputPaths(pub HashMap<u8, String>);
+
+impl InputPaths {
+    pub fn get(&self, day: u8, source: InputSource) -> String {
+        self.0
+            .get(&day)
+            .cloned()
+            .unwrap_or_else(|| source.path(day))
+    }
+}
+
+/// Every registered day's puzzle input, keyed by day number.
+#[derive(Resource, Default, Debug)]
+pub struct PuzzleInputs(HashMap<u8, UntypedHandle>);
+
+/// The puzzle inputs that failed to load last time, keyed by day number. Their days can't be
+/// opened from the menu.
+#[derive(Resource, Default, Debug)]
+pub struct BrokenInputs(HashMap<u8, Arc<AssetLoadError>>);
+
+impl BrokenInputs {
+    pub fn get(&self, day: u8) -> Option<&AssetLoadError> {
+        self.0.get(&day).map(|error| &**error)
+    }
+
+    pub fn contains(&self, day: u8) -> bool {
+        self.0.contains_key(&day)
+    }
+
+    /// Every broken input, ordered by day number.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &AssetLoadError)> {
+        let mut broken: Vec<_> = self.0.iter().map(|(&day, error)| (day, &**error)).collect();
+        broken.sort_by_key(|(day, _)| *day);
+        broken.into_iter()
+    }
+}
+
+impl PuzzleInputs {
+    /// The loaded puzzle input for a day, if it is available.
+    pub fn get<'a, S: Solution>(&self, assets: &'a Assets<S::Input>) -> Option<&'a S::Input> {
+        assets.get(self.id::<S>()?)
+    }
+
+    pub fn id<S: Solution>(&self) -> Option<AssetId<S::Input>> {
+        Some(self.0.get(&S::DAY)?.id().typed::<S::Input>())
+    }
+}
+
+// Implemented by hand rather than derived so that the handles can come from the day registry and
+// the paths from `InputPaths` and `InputSource`. Re-entering `AoCState::Loading` reloads them.
+impl AssetCollection for PuzzleInputs {
+    fn create(world: &mut World) -> Self {
+        let paths = world.resource::<InputPaths>();
+        let source = *world.resource::<InputSource>();
+        let asset_server = world.resource::<AssetServer>();
+        Self(
+            world
+                .resource::<Days>()
+                .iter()
+                .map(|day| {
+                    let path = paths.get(day.number, source);
+                    // An override is from outside the assets directory, so it may not have the
+                    // `.meta` file that the day's input there has.
+                    let without_meta = paths.0.contains_key(&day.number)
+                        && !Path::new(&format!("{path}.meta")).exists();
+                    let handle = if without_meta {
+                        day.load_input_without_meta(asset_server, path)
+                    } else {
+                        day.load_input(asset_server, path)
+                    };
+                    (day.number, handle)
+                })
+                .collect(),
+        )
+    }
+
+    // The collection is inserted as soon as loading starts, rather than only once every input has
+    // loaded, so that the days with good inputs can still be solved if another day's is broken.
+    fn load(world: &mut World) -> Vec<UntypedHandle> {
+        let inputs = Self::create(world);
+        let handles = inputs.0.values().cloned().collect();
+        world.insert_resource(inputs);
+        handles
+    }
+}
+
+fn find_broken_inputs(
+    puzzle_inputs: Res<PuzzleInputs>,
+    asset_server: Res<AssetServer>,
+    mut broken: ResMut<BrokenInputs>,
+) {
+    broken.0 = puzzle_inputs
+        .0
+        .iter()
+        .filter_map(|(&day, handle)| match asset_server.load_state(handle) {
+            LoadState::Failed(error) => Some((day, error)),
+            _ => None,
+        })
+        .collect();
+    for (day, error) in broken.iter() {
+        error!("Day {day} input failed to load: {error}");
+    }
+}
+
+// Lists what failed to load, with a way through to the menu for the days that did.
+fn show_broken_inputs(mut commands: Commands, broken: Res<BrokenInputs>) {
+    let mut lines: Vec<String> = broken
+        .iter()
+        .map(|(day, error)| format!("Day {day}: {error}"))
+        .collect();
+    if lines.is_empty() {
+        // Something other than a puzzle input, such as the expected answers.
+        lines.push("Some other asset failed to load, see the log for details".into());
+    }
+
+    commands
+        .spawn((
+            Name::new("Loading Failed"),
+            Node {
+                align_items: AlignItems::Start,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(5.),
+                ..default()
+            },
+            StateScoped(AoCState::LoadingFailed),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Some puzzle inputs could not be loaded"),
+                TextFont {
+                    font_size: 30.,
+                    ..default()
+                },
+            ));
+            for line in lines {
+                p.spawn((
+                    Text::new(line),
+                    TextColor(RED.into()),
+                    TextFont {
+                        font_size: 14.,
+                        ..default()
+                    },
+                ));
+            }
+            ui::spawn_button(
+                p,
+                "Continue to menu",
+                (),
+                |_ev: Trigger<Pointer<Click>>, mut next_state: ResMut<NextState<AoCState>>| {
+                    next_state.set(AoCState::Menu);
+                },
+            )
+            .insert(Node {
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::top(Val::Px(10.)),
+                padding: UiRect::axes(Val::Px(10.), Val::Px(5.)),
+                ..default()
+            });
+        });
 }
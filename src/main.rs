@@ -1,35 +1,129 @@
 // #![warn(clippy::pedantic)]
 #![warn(clippy::nursery)]
 use bevy::{dev_tools::states::log_transitions, prelude::*};
+use clap::Parser;
+use loading::InputSource;
+use settings::Settings;
 
+mod answer;
+mod cli;
+mod client;
 mod days;
+mod expected_answers_asset;
+mod export;
+mod failure;
+mod fetch;
+mod grid;
+#[cfg(test)]
+mod harness;
+mod headless;
 mod loading;
 mod menu;
+mod navigation;
 mod puzzle_input_asset;
+mod puzzle_input_grid_asset;
 mod puzzle_input_lines_asset;
 mod puzzle_input_string_asset;
+mod results;
+mod settings;
+mod simulation;
+mod submit;
+mod tasks;
+#[cfg(test)]
+mod temp_dir;
+mod terminal;
+mod timeline;
+mod timing;
+mod ui;
+mod verify;
 
 #[derive(States, Debug, Hash, PartialEq, Eq, Copy, Clone, Default)]
 pub enum AoCState {
     #[default]
     Loading,
+    /// Some assets failed to load. Days with good inputs can still be opened from the menu.
+    LoadingFailed,
     Menu,
-    Day1,
-    Day2,
-    Day3,
-    Day4,
-    Day5,
-    Day6,
-    Day7,
-    Day8,
-    Day9,
+    Day(u8),
 }
 
-fn main() {
-    App::new()
-        .add_plugins((DefaultPlugins, loading::plugin, menu::plugin, days::plugin))
-        .init_state::<AoCState>()
-        .enable_state_scoped_entities::<AoCState>()
-        .add_systems(Update, log_transitions::<AoCState>)
-        .run();
+fn main() -> AppExit {
+    let cli = cli::Cli::parse();
+    let source = if cli.sample {
+        InputSource::Sample
+    } else {
+        InputSource::Real
+    };
+
+    match cli.command {
+        Some(cli::Command::Run { day, part, input }) => headless::run(day, part, input, source),
+        Some(cli::Command::Bench { day, runs, out }) => headless::bench(day, runs, &out, source),
+        Some(cli::Command::Batch { dir, day }) => headless::batch(&dir, day),
+        Some(cli::Command::Export {
+            day,
+            out,
+            frames,
+            scale,
+        }) => headless::export(day, &out, frames as usize, scale, source),
+        Some(cli::Command::Show { day, frames, fps }) => {
+            headless::show(day, frames as usize, fps, source)
+        }
+        Some(cli::Command::Fetch { day, base_url }) => fetch::run(day, base_url),
+        Some(cli::Command::Submit {
+            day,
+            part,
+            answer,
+            base_url,
+        }) => submit::run(day, part, answer, base_url),
+        None => {
+            let settings = Settings::load_or_default();
+            let source = if cli.sample {
+                InputSource::Sample
+            } else {
+                settings.source
+            };
+            let solve_all = cli.solve_all || settings.solve_all;
+            App::new()
+                .add_plugins((
+                    DefaultPlugins.set(WindowPlugin {
+                        primary_window: Some(Window {
+                            resolution: settings.window.into(),
+                            ..default()
+                        }),
+                        ..default()
+                    }),
+                    answer::plugin,
+                    failure::plugin,
+                    fetch::plugin,
+                    timing::plugin,
+                    loading::plugin,
+                    menu::plugin,
+                    navigation::plugin,
+                    results::plugin,
+                    settings::plugin,
+                    simulation::plugin,
+                    tasks::plugin,
+                    days::plugin,
+                    verify::plugin,
+                ))
+                .insert_resource(source)
+                .insert_resource(settings)
+                .init_state::<AoCState>()
+                .enable_state_scoped_entities::<AoCState>()
+                .add_systems(Startup, spawn_camera)
+                .add_systems(
+                    OnEnter(AoCState::Menu),
+                    results::solve_all
+                        .run_if(run_once)
+                        .run_if(move || solve_all),
+                )
+                .add_systems(Update, log_transitions::<AoCState>)
+                .run()
+        }
+    }
+}
+
+// One camera for the whole app, which every screen's UI is drawn with.
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((Name::new("Camera"), Camera2d));
 }
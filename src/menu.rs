@@ -1,80 +1,358 @@
 use bevy::{
-    color::palettes::css::{FIRE_BRICK, GREEN},
+    color::palettes::css::{DIM_GRAY, FIRE_BRICK, GOLD, GREEN, LIMEGREEN, RED},
     prelude::*,
 };
 
-use crate::AoCState;
+use crate::{
+    answer::Part,
+    days::{DayInfo, Days},
+    fetch,
+    loading::{BrokenInputs, InputSource},
+    results::{self, Results},
+    ui,
+    verify::{Verdict, Verdicts},
+    AoCState,
+};
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(AoCState::Menu), init);
+    app.add_systems(OnEnter(AoCState::Menu), init)
+        .add_systems(Update, update_dashboard.run_if(in_state(AoCState::Menu)));
 }
 
 #[derive(Component)]
 struct Menu;
 
-fn init(mut commands: Commands) {
-    commands.spawn((Name::new("Camera"), Camera2d));
-
-    commands.spawn((
-        Name::new("Menu"),
-        Menu,
-        Node {
-            align_items: AlignItems::Center,
-            align_self: AlignSelf::Center,
-            justify_content: JustifyContent::Center,
-            justify_self: JustifySelf::Center,
-            padding: UiRect::all(Val::Px(10.)),
-            ..default()
-        },
-        StateScoped(AoCState::Menu),
-    ));
-
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day1, "One".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day2, "Two".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day3, "Three".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day4, "Four".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day5, "Five".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day6, "Six".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day7, "Seven".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day8, "Eight".into()));
-    commands.run_system_cached_with(spawn_puzzle_link, (AoCState::Day9, "Nine".into()));
+/// The grid of day cards.
+#[derive(Component)]
+struct Dashboard;
+
+/// The row of buttons under the dashboard.
+#[derive(Component)]
+struct Toolbar;
+
+/// A part of a day's card that changes as results come in.
+#[derive(Component, Debug)]
+struct CardField(u8, Field);
+
+#[derive(Debug)]
+enum Field {
+    Star(Part),
+    Verdict(Part),
+    Answers,
+    Timings,
 }
 
-fn spawn_puzzle_link(
-    state: In<(AoCState, String)>,
-    mut commands: Commands,
-    menu: Single<Entity, With<Menu>>,
-) {
-    commands.entity(*menu).with_children(|p| {
-        let (day, label) = state.0;
-        p.spawn((
-            Button,
-            BackgroundColor(FIRE_BRICK.into()),
-            BorderColor(GREEN.into()),
+fn init(mut commands: Commands, days: Res<Days>) {
+    commands
+        .spawn((
+            Name::new("Menu"),
+            Menu,
             Node {
                 align_items: AlignItems::Center,
-                border: UiRect::all(Val::Px(2.)),
-                height: Val::Px(50.),
+                align_self: AlignSelf::Center,
+                flex_direction: FlexDirection::Column,
                 justify_content: JustifyContent::Center,
-                margin: UiRect::all(Val::Px(5.)),
-                padding: UiRect::all(Val::Px(20.)),
-                width: Val::Px(50.),
+                justify_self: JustifySelf::Center,
+                padding: UiRect::all(Val::Px(10.)),
                 ..default()
             },
+            StateScoped(AoCState::Menu),
         ))
         .with_children(|p| {
             p.spawn((
-                Text::new(label),
+                Name::new("Dashboard"),
+                Dashboard,
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::px(5, 200.),
+                    column_gap: Val::Px(10.),
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Name::new("Toolbar"),
+                Toolbar,
+                Node {
+                    margin: UiRect::top(Val::Px(10.)),
+                    ..default()
+                },
+            ));
+        });
+
+    for day in days.iter() {
+        commands.run_system_cached_with(spawn_day_card, *day);
+    }
+    commands.run_system_cached(spawn_input_toggle);
+    commands.run_system_cached(spawn_fetch_button);
+    commands.run_system_cached(spawn_solve_all_button);
+    commands.run_system_cached(spawn_cancel_button);
+}
+
+// Each card's stars, answers, timings and verdicts start empty, and are filled in by
+// `update_dashboard`.
+fn spawn_day_card(
+    day: In<DayInfo>,
+    mut commands: Commands,
+    dashboard: Single<Entity, With<Dashboard>>,
+    broken: Res<BrokenInputs>,
+) {
+    // A day whose input didn't load has nothing to solve, so it is shown but can't be opened.
+    let broken = broken.contains(day.number);
+    commands.entity(*dashboard).with_children(|p| {
+        let state = AoCState::Day(day.number);
+        let mut card = p.spawn((
+            Name::new(format!("Day {}: {}", day.number, day.title)),
+            Button,
+            BackgroundColor(if broken { DIM_GRAY } else { FIRE_BRICK }.into()),
+            BorderColor(if broken { RED } else { GREEN }.into()),
+            Node {
+                border: UiRect::all(Val::Px(2.)),
+                flex_direction: FlexDirection::Column,
+                min_height: Val::Px(110.),
+                padding: UiRect::all(Val::Px(5.)),
+                row_gap: Val::Px(2.),
+                ..default()
+            },
+        ));
+        card.with_children(|p| {
+            p.spawn(Node {
+                column_gap: Val::Px(5.),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(format!("Day {}", day.number)),
+                    TextFont {
+                        font_size: 14.,
+                        ..default()
+                    },
+                ));
+                for part in [Part::A, Part::B] {
+                    p.spawn((
+                        CardField(day.number, Field::Star(part)),
+                        Text::new("*"),
+                        TextFont {
+                            font_size: 14.,
+                            ..default()
+                        },
+                    ));
+                }
+            });
+            p.spawn((
+                Text::new(day.title),
+                TextFont {
+                    font_size: 12.,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                CardField(day.number, Field::Answers),
+                Text::default(),
                 TextFont {
-                    font_size: 14.,
+                    font_size: 12.,
                     ..default()
                 },
             ));
-        })
-        .observe(
-            move |_ev: Trigger<Pointer<Click>>, mut next_state: ResMut<NextState<AoCState>>| {
-                next_state.set(day);
+            p.spawn((
+                CardField(day.number, Field::Timings),
+                Text::default(),
+                TextFont {
+                    font_size: 10.,
+                    ..default()
+                },
+            ));
+            p.spawn(Node {
+                column_gap: Val::Px(2.),
+                margin: UiRect::top(Val::Px(2.)),
+                ..default()
+            })
+            .with_children(|p| {
+                for part in [Part::A, Part::B] {
+                    p.spawn((
+                        CardField(day.number, Field::Verdict(part)),
+                        BackgroundColor(DIM_GRAY.into()),
+                        Node {
+                            justify_content: JustifyContent::Center,
+                            width: Val::Px(14.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            Text::new(format!("{part:?}")),
+                            TextFont {
+                                font_size: 10.,
+                                ..default()
+                            },
+                        ));
+                    });
+                }
+            });
+        });
+        if !broken {
+            card.observe(
+                move |_ev: Trigger<Pointer<Click>>, mut next_state: ResMut<NextState<AoCState>>| {
+                    next_state.set(state);
+                },
+            );
+        }
+    });
+}
+
+// Fills in each card from the latest results, whenever they change or the menu is opened.
+fn update_dashboard(
+    mut fields: Query<(
+        &CardField,
+        Option<&mut Text>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+    )>,
+    added: Query<(), Added<CardField>>,
+    results: Res<Results>,
+    verdicts: Res<Verdicts>,
+    broken: Res<BrokenInputs>,
+) {
+    if added.is_empty() && !results.is_changed() && !verdicts.is_changed() {
+        return;
+    }
+
+    for (CardField(day, field), text, color, background) in &mut fields {
+        let day_results = results.get(*day);
+        let answer = |part| day_results.and_then(|r| r.answers.get(&part));
+        match (field, text, color, background) {
+            (Field::Star(part), _, Some(mut color), _) => {
+                // A star for every answer that isn't known to be wrong.
+                let earned = answer(*part).is_some() && verdicts.get(*day, *part) != Verdict::Fail;
+                color.0 = if earned { GOLD } else { DIM_GRAY }.into();
+            }
+            (Field::Verdict(part), _, _, Some(mut background)) => {
+                background.0 = verdict_color(verdicts.get(*day, *part));
+            }
+            (Field::Answers, Some(mut text), _, _) => {
+                text.0 = if broken.contains(*day) {
+                    "Input didn't load".into()
+                } else if results.is_solving(*day) {
+                    "Solving...".into()
+                } else if day_results.is_some_and(|r| r.error.is_some()) {
+                    "Failed".into()
+                } else {
+                    [Part::A, Part::B]
+                        .into_iter()
+                        .filter_map(|part| answer(part).map(|value| format!("{part:?}: {value}")))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                };
+            }
+            (Field::Timings, Some(mut text), _, _) => {
+                text.0 = day_results
+                    .map(|r| {
+                        r.stages
+                            .iter()
+                            .map(|(stage, elapsed)| format!("{stage} {elapsed:.2?}"))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Switches every day between the real inputs and the samples, reloading them all.
+fn spawn_input_toggle(
+    mut commands: Commands,
+    toolbar: Single<Entity, With<Toolbar>>,
+    source: Res<InputSource>,
+) {
+    let label = match *source {
+        InputSource::Real => "Real input",
+        InputSource::Sample => "Sample input",
+    };
+    commands.entity(*toolbar).with_children(|p| {
+        ui::spawn_button(
+            p,
+            label,
+            (),
+            |_ev: Trigger<Pointer<Click>>,
+             mut source: ResMut<InputSource>,
+             mut verdicts: ResMut<Verdicts>,
+             mut results: ResMut<Results>,
+             mut next_state: ResMut<NextState<AoCState>>| {
+                *source = source.toggled();
+                // Any results so far were for the other set of inputs.
+                verdicts.clear();
+                results.clear();
+                next_state.set(AoCState::Loading);
             },
-        );
+        )
+        .insert(toolbar_node());
     });
 }
+
+// Downloads the inputs for any days that don't have one yet, then reloads them.
+fn spawn_fetch_button(mut commands: Commands, toolbar: Single<Entity, With<Toolbar>>) {
+    commands.entity(*toolbar).with_children(|p| {
+        ui::spawn_button(
+            p,
+            "Fetch inputs",
+            (),
+            |_ev: Trigger<Pointer<Click>>, mut commands: Commands| {
+                commands.run_system_cached(fetch::fetch_missing);
+            },
+        )
+        .insert(toolbar_node());
+    });
+}
+
+// Solves every day in the background, filling in the dashboard as each one finishes.
+fn spawn_solve_all_button(mut commands: Commands, toolbar: Single<Entity, With<Toolbar>>) {
+    commands.entity(*toolbar).with_children(|p| {
+        ui::spawn_button(
+            p,
+            "Solve all",
+            (),
+            |_ev: Trigger<Pointer<Click>>, mut commands: Commands| {
+                commands.run_system_cached(results::solve_all);
+            },
+        )
+        .insert(toolbar_node());
+    });
+}
+
+// Stops solving days in the background, e.g. when one is taking too long.
+fn spawn_cancel_button(mut commands: Commands, toolbar: Single<Entity, With<Toolbar>>) {
+    commands.entity(*toolbar).with_children(|p| {
+        ui::spawn_button(
+            p,
+            "Cancel solves",
+            (),
+            |_ev: Trigger<Pointer<Click>>, mut commands: Commands| {
+                commands.run_system_cached(results::cancel_all);
+            },
+        )
+        .insert(toolbar_node());
+    });
+}
+
+// The toolbar's buttons are taller than the shared ones, and spaced apart.
+fn toolbar_node() -> Node {
+    Node {
+        align_items: AlignItems::Center,
+        border: UiRect::all(Val::Px(2.)),
+        height: Val::Px(50.),
+        justify_content: JustifyContent::Center,
+        margin: UiRect::all(Val::Px(5.)),
+        padding: UiRect::horizontal(Val::Px(10.)),
+        ..default()
+    }
+}
+
+fn verdict_color(verdict: Verdict) -> Color {
+    match verdict {
+        Verdict::Pass => LIMEGREEN.into(),
+        Verdict::Fail => RED.into(),
+        Verdict::Unknown => DIM_GRAY.into(),
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+
+use crate::{
+    days::{DayRoot, Days},
+    loading::BrokenInputs,
+    ui, AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, (navigate, spawn_breadcrumbs));
+}
+
+// How long to wait for a second digit before jumping to the day typed so far.
+const TYPING_DELAY_SECS: f32 = 0.5;
+
+/// The days that can be opened, in order. Days whose input didn't load are left out.
+fn open_days(days: &Days, broken: &BrokenInputs) -> Vec<u8> {
+    days.iter()
+        .map(|day| day.number)
+        .filter(|day| !broken.contains(*day))
+        .collect()
+}
+
+/// The day before or after `current` in `open`, if there is one.
+fn neighbour(open: &[u8], current: u8, forward: bool) -> Option<u8> {
+    if forward {
+        open.iter().copied().find(|day| *day > current)
+    } else {
+        open.iter().copied().rev().find(|day| *day < current)
+    }
+}
+
+const fn key_digit(key: KeyCode) -> Option<u8> {
+    Some(match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => 0,
+        KeyCode::Digit1 | KeyCode::Numpad1 => 1,
+        KeyCode::Digit2 | KeyCode::Numpad2 => 2,
+        KeyCode::Digit3 | KeyCode::Numpad3 => 3,
+        KeyCode::Digit4 | KeyCode::Numpad4 => 4,
+        KeyCode::Digit5 | KeyCode::Numpad5 => 5,
+        KeyCode::Digit6 | KeyCode::Numpad6 => 6,
+        KeyCode::Digit7 | KeyCode::Numpad7 => 7,
+        KeyCode::Digit8 | KeyCode::Numpad8 => 8,
+        KeyCode::Digit9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    })
+}
+
+/// A day number being typed, which may be waiting for its second digit.
+#[derive(Debug)]
+struct Typed {
+    day: u8,
+    elapsed: f32,
+}
+
+// Escape goes back to the menu, the arrow keys and PageUp/PageDown move between days, and typing a
+// day's number opens it.
+fn navigate(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    days: Res<Days>,
+    broken: Res<BrokenInputs>,
+    state: Res<State<AoCState>>,
+    mut next_state: ResMut<NextState<AoCState>>,
+    mut typed: Local<Option<Typed>>,
+) {
+    let current = match *state.get() {
+        AoCState::Menu => None,
+        AoCState::Day(day) => Some(day),
+        // Nothing to navigate to until the inputs have loaded, or the failures have been read.
+        AoCState::Loading | AoCState::LoadingFailed => {
+            *typed = None;
+            return;
+        }
+    };
+    let open = open_days(&days, &broken);
+    if let Some(target) = target(&keys, time.delta_secs(), &open, current, &mut typed) {
+        next_state.set(target);
+    }
+}
+
+// Works out where the keys pressed this frame lead, if anywhere new.
+fn target(
+    keys: &ButtonInput<KeyCode>,
+    delta: f32,
+    open: &[u8],
+    current: Option<u8>,
+    typed: &mut Option<Typed>,
+) -> Option<AoCState> {
+    if let Some(current) = current {
+        if keys.just_pressed(KeyCode::Escape) {
+            return Some(AoCState::Menu);
+        }
+        if keys.any_just_pressed([KeyCode::ArrowLeft, KeyCode::PageUp]) {
+            return neighbour(open, current, false).map(AoCState::Day);
+        }
+        if keys.any_just_pressed([KeyCode::ArrowRight, KeyCode::PageDown]) {
+            return neighbour(open, current, true).map(AoCState::Day);
+        }
+    }
+
+    for digit in keys.get_just_pressed().filter_map(|key| key_digit(*key)) {
+        let day = match typed {
+            Some(typed) if typed.day < 10 => typed.day * 10 + digit,
+            _ => digit,
+        };
+        *typed = Some(Typed { day, elapsed: 0. });
+    }
+    let pending = typed.as_mut()?;
+    pending.elapsed += delta;
+    // Don't wait for a second digit that couldn't make a day, e.g. after a 3 when there are only
+    // days up to 29.
+    let longer = open.iter().any(|day| *day >= 10 && day / 10 == pending.day);
+    if longer && pending.elapsed < TYPING_DELAY_SECS {
+        return None;
+    }
+    let day = typed.take()?.day;
+    (open.contains(&day) && current != Some(day)).then_some(AoCState::Day(day))
+}
+
+// Adds a header to each day, with a way back to the menu and on to the days either side.
+fn spawn_breadcrumbs(
+    mut commands: Commands,
+    roots: Query<(Entity, &DayRoot), Added<DayRoot>>,
+    days: Res<Days>,
+    broken: Res<BrokenInputs>,
+) {
+    let open = open_days(&days, &broken);
+    for (entity, root) in &roots {
+        let header = commands
+            .spawn((
+                Name::new("Breadcrumbs"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(5.),
+                    margin: UiRect::bottom(Val::Px(10.)),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                spawn_link(p, "Menu", AoCState::Menu);
+                p.spawn((
+                    Text::new(format!("/ Day {}", root.0)),
+                    TextFont {
+                        font_size: 14.,
+                        ..default()
+                    },
+                ));
+                if let Some(day) = neighbour(&open, root.0, false) {
+                    spawn_link(p, &format!("< Day {day}"), AoCState::Day(day));
+                }
+                if let Some(day) = neighbour(&open, root.0, true) {
+                    spawn_link(p, &format!("Day {day} >"), AoCState::Day(day));
+                }
+            })
+            .id();
+        commands.entity(entity).insert_children(0, &[header]);
+    }
+}
+
+fn spawn_link(p: &mut ChildBuilder, label: &str, target: AoCState) {
+    ui::spawn_button(
+        p,
+        label,
+        (),
+        move |_ev: Trigger<Pointer<Click>>, mut next_state: ResMut<NextState<AoCState>>| {
+            next_state.set(target);
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbour_skips_missing_days() {
+        let open = [1, 2, 4, 7];
+        assert_eq!(neighbour(&open, 2, true), Some(4));
+        assert_eq!(neighbour(&open, 4, false), Some(2));
+        assert_eq!(neighbour(&open, 7, true), None);
+        assert_eq!(neighbour(&open, 1, false), None);
+        // A day that can't be opened still has neighbours, e.g. if its input broke while open.
+        assert_eq!(neighbour(&open, 3, true), Some(4));
+    }
+
+    #[test]
+    fn test_typing_a_day() {
+        let open: Vec<u8> = (1..=25).collect();
+        let mut keys = ButtonInput::default();
+        let mut typed = None;
+
+        // A 1 could be the start of 10 to 19, so wait for another digit.
+        keys.press(KeyCode::Digit1);
+        assert_eq!(target(&keys, 0.1, &open, None, &mut typed), None);
+        keys.clear();
+        keys.press(KeyCode::Numpad2);
+        assert_eq!(
+            target(&keys, 0.1, &open, None, &mut typed),
+            Some(AoCState::Day(12))
+        );
+
+        // A 7 can't start anything longer, so it opens straight away.
+        keys.clear();
+        keys.press(KeyCode::Digit7);
+        assert_eq!(
+            target(&keys, 0.1, &open, Some(12), &mut typed),
+            Some(AoCState::Day(7))
+        );
+
+        // Without a second digit, a 2 opens day 2 once the wait is over.
+        keys.clear();
+        keys.press(KeyCode::Digit2);
+        assert_eq!(target(&keys, 0.1, &open, None, &mut typed), None);
+        keys.clear();
+        assert_eq!(
+            target(&keys, TYPING_DELAY_SECS, &open, None, &mut typed),
+            Some(AoCState::Day(2))
+        );
+    }
+}
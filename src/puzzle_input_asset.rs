@@ -1,23 +1,170 @@
+use std::{num::ParseIntError, str::FromStr};
+
 use bevy::{
     asset::{io::Reader, AssetLoader, LoadContext},
     prelude::*,
     reflect::TypePath,
 };
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Rows of numbers, optionally split into sections at blank lines.
 #[derive(Asset, TypePath, Debug)]
-pub(crate) struct PuzzleInputAsset {
-    #[allow(dead_code)]
-    pub rows: Vec<Vec<i32>>,
+pub struct PuzzleInputAsset {
+    numbers: Numbers,
+    // The line each section starts on, so rows can be traced back to the source.
+    first_lines: Vec<usize>,
+    /// The text the rows were parsed from, for pointing errors at the right line.
+    pub source: String,
+}
+
+impl PuzzleInputAsset {
+    /// Parses `source` as the loader would, naming it `name` in any error.
+    pub fn parse(
+        name: &str,
+        source: String,
+        settings: &PuzzleInputSettings,
+    ) -> Result<Self, PuzzleInputAssetLoaderError> {
+        let parsed = match settings.number_type {
+            NumberType::I32 => parse_sections(&source, settings).map(|(s, l)| (Numbers::I32(s), l)),
+            NumberType::I64 => parse_sections(&source, settings).map(|(s, l)| (Numbers::I64(s), l)),
+            NumberType::U64 => parse_sections(&source, settings).map(|(s, l)| (Numbers::U64(s), l)),
+        };
+        match parsed {
+            Ok((numbers, first_lines)) => Ok(Self {
+                numbers,
+                first_lines,
+                source,
+            }),
+            Err((span, error)) => Err(PuzzleInputAssetLoaderError::IntegerConversionError {
+                input: NamedSource::new(name, source),
+                span,
+                number_type: settings.number_type,
+                error,
+            }),
+        }
+    }
+
+    /// Every section's rows, or `None` if the numbers were loaded as a different type.
+    pub fn sections<N: Number>(&self) -> Option<&[Vec<Vec<N>>]> {
+        N::sections(&self.numbers).map(Vec::as_slice)
+    }
+
+    /// The rows of the first section, which is all of them unless the input was split into
+    /// sections. `None` if the numbers were loaded as a different type.
+    pub fn rows<N: Number>(&self) -> Option<&[Vec<N>]> {
+        Some(self.sections()?.first().map_or(&[], Vec::as_slice))
+    }
+
+    /// The zero-based line of [`Self::source`] that a row was parsed from.
+    pub fn line(&self, section: usize, row: usize) -> usize {
+        self.first_lines[section] + row
+    }
+}
+
+/// The numbers in a [`PuzzleInputAsset`], by section and then by row.
+#[derive(Debug)]
+pub enum Numbers {
+    I32(Vec<Vec<Vec<i32>>>),
+    I64(Vec<Vec<Vec<i64>>>),
+    U64(Vec<Vec<Vec<u64>>>),
+}
+
+/// A type the numbers in a [`PuzzleInputAsset`] can be loaded as, picked with [`NumberType`].
+pub trait Number: FromStr<Err = ParseIntError> {
+    fn sections(numbers: &Numbers) -> Option<&Vec<Vec<Vec<Self>>>>;
+}
+
+impl Number for i32 {
+    fn sections(numbers: &Numbers) -> Option<&Vec<Vec<Vec<Self>>>> {
+        match numbers {
+            Numbers::I32(sections) => Some(sections),
+            _ => None,
+        }
+    }
+}
+
+impl Number for i64 {
+    fn sections(numbers: &Numbers) -> Option<&Vec<Vec<Vec<Self>>>> {
+        match numbers {
+            Numbers::I64(sections) => Some(sections),
+            _ => None,
+        }
+    }
+}
+
+impl Number for u64 {
+    fn sections(numbers: &Numbers) -> Option<&Vec<Vec<Vec<Self>>>> {
+        match numbers {
+            Numbers::U64(sections) => Some(sections),
+            _ => None,
+        }
+    }
+}
+
+/// What separates the numbers on each line.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Any number of spaces.
+    #[default]
+    Space,
+    Comma,
+    Tab,
+    /// Any one of these characters, e.g. `"|,"` for both `47|53` and `75,47,61`.
+    Custom(String),
+}
+
+impl Delimiter {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Space => c == ' ',
+            Self::Comma => c == ',',
+            Self::Tab => c == '\t',
+            Self::Custom(chars) => chars.contains(c),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberType {
+    #[default]
+    I32,
+    I64,
+    U64,
+}
+
+/// Settings for [`PuzzleInputAssetLoader`]. Set them for a single input with a `.meta` file next
+/// to it, e.g. `input/5.aoc.meta`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct PuzzleInputSettings {
+    pub delimiter: Delimiter,
+    pub number_type: NumberType,
+    /// Ignore whitespace around each number, e.g. the spaces in `1, 2, 3`.
+    pub trim: bool,
+    /// Start a new section at each blank line, rather than reading it as an empty row.
+    pub sections: bool,
+}
+
+impl Default for PuzzleInputSettings {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::Space,
+            number_type: NumberType::I32,
+            trim: true,
+            sections: false,
+        }
+    }
 }
 
 #[derive(Default)]
-pub(crate) struct PuzzleInputAssetLoader;
+pub struct PuzzleInputAssetLoader;
 
 /// Possible errors that can be produced by [`PuzzleInputAssetLoader`]
 #[non_exhaustive]
-#[derive(Debug, Error)]
-pub(crate) enum PuzzleInputAssetLoaderError {
+#[derive(Debug, Error, Diagnostic)]
+pub enum PuzzleInputAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load asset: {0}")]
     Io(#[from] std::io::Error),
@@ -27,44 +174,206 @@ pub(crate) enum PuzzleInputAssetLoaderError {
     FromUtf8(#[from] std::string::FromUtf8Error),
 
     /// An [integer conversion](std::num::ParseIntError)
-    #[error("Could not convert to integer: {0}")]
-    IntegerConversionError(#[from] std::num::ParseIntError),
+    #[error("Could not convert to integer")]
+    #[diagnostic(
+        code(puzzle_input::integer),
+        help("the loader settings for this input expect {number_type:?} numbers")
+    )]
+    IntegerConversionError {
+        #[source_code]
+        input: NamedSource<String>,
+        #[label("not an integer")]
+        span: SourceSpan,
+        number_type: NumberType,
+        #[source]
+        error: ParseIntError,
+    },
 }
 
 impl AssetLoader for PuzzleInputAssetLoader {
     type Asset = PuzzleInputAsset;
-    type Settings = ();
+    type Settings = PuzzleInputSettings;
     type Error = PuzzleInputAssetLoaderError;
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _settings: &(),
-        _load_context: &mut LoadContext<'_>,
+        settings: &PuzzleInputSettings,
+        load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
         let file_contents = String::from_utf8(bytes)?;
-        let lines = file_contents.lines();
-
-        let mut rows: Vec<Vec<i32>> = vec![];
-        for line in lines {
-            let cols: Vec<&str> = line.split(" ").collect();
-
-            let mut row: Vec<i32> = vec![];
-            for col in cols {
-                if col.is_empty() {
-                    continue;
-                }
-                let n: i32 = col.parse()?;
-                row.push(n);
-            }
 
-            rows.push(row);
-        }
-        Ok(PuzzleInputAsset { rows })
+        PuzzleInputAsset::parse(
+            &load_context.path().display().to_string(),
+            file_contents,
+            settings,
+        )
     }
 
     fn extensions(&self) -> &[&str] {
         &["aoc"]
     }
 }
+
+type Sections<N> = (Vec<Vec<Vec<N>>>, Vec<usize>);
+
+// Returns each section's rows and the line each section starts on, or the span of the first
+// number that didn't parse.
+fn parse_sections<N: Number>(
+    input: &str,
+    settings: &PuzzleInputSettings,
+) -> Result<Sections<N>, (SourceSpan, ParseIntError)> {
+    let mut sections: Vec<Vec<Vec<N>>> = vec![vec![]];
+    let mut first_lines = vec![0];
+    for (i, line) in input.lines().enumerate() {
+        if settings.sections && line.trim().is_empty() {
+            // Several blank lines in a row only end one section, and so do any at the start.
+            if sections.last().is_some_and(Vec::is_empty) {
+                *first_lines.last_mut().unwrap() = i + 1;
+            } else {
+                sections.push(vec![]);
+                first_lines.push(i + 1);
+            }
+            continue;
+        }
+
+        let mut row = vec![];
+        for col in line.split(|c| settings.delimiter.matches(c)) {
+            let col = if settings.trim { col.trim() } else { col };
+            if col.is_empty() {
+                continue;
+            }
+            // `col` borrows from `input`, so this is where it starts in the file.
+            let offset = col.as_ptr() as usize - input.as_ptr() as usize;
+            row.push(
+                col.parse()
+                    .map_err(|error| ((offset, col.len()).into(), error))?,
+            );
+        }
+        sections.last_mut().unwrap().push(row);
+    }
+
+    if sections.last().is_some_and(Vec::is_empty) {
+        sections.pop();
+        first_lines.pop();
+    }
+    Ok((sections, first_lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str, settings: PuzzleInputSettings) -> PuzzleInputAsset {
+        PuzzleInputAsset::parse("test.aoc", input.into(), &settings).unwrap()
+    }
+
+    fn with_delimiter(delimiter: Delimiter) -> PuzzleInputSettings {
+        PuzzleInputSettings {
+            delimiter,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_delimiters() {
+        let rows = |input: &str, delimiter| {
+            parse(input, with_delimiter(delimiter))
+                .rows::<i32>()
+                .unwrap()
+                .to_vec()
+        };
+        // Runs of spaces don't leave empty numbers between them.
+        assert_eq!(rows("3   4\n1 2\n", Delimiter::Space), [[3, 4], [1, 2]]);
+        assert_eq!(
+            rows("1,2,3\n4,5", Delimiter::Comma),
+            [vec![1, 2, 3], vec![4, 5]]
+        );
+        assert_eq!(rows("1\t2\n3\t4", Delimiter::Tab), [[1, 2], [3, 4]]);
+        assert_eq!(
+            rows("47|53\n75,47,61", Delimiter::Custom("|,".into())),
+            [vec![47, 53], vec![75, 47, 61]]
+        );
+    }
+
+    #[test]
+    fn test_number_types() {
+        let settings = |number_type| PuzzleInputSettings {
+            number_type,
+            ..default()
+        };
+        let puzzle = parse("9000000000 -1", settings(NumberType::I64));
+        assert_eq!(puzzle.rows::<i64>().unwrap(), [vec![9_000_000_000, -1]]);
+        // Asking for a different type than was loaded gives nothing, rather than converting.
+        assert_eq!(puzzle.rows::<i32>(), None);
+
+        let puzzle = parse("18446744073709551615", settings(NumberType::U64));
+        assert_eq!(puzzle.rows::<u64>().unwrap(), [vec![u64::MAX]]);
+        assert!(
+            PuzzleInputAsset::parse("test.aoc", "-1".into(), &settings(NumberType::U64)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_trim() {
+        let input = "1, 2 ,3";
+        let puzzle = parse(input, with_delimiter(Delimiter::Comma));
+        assert_eq!(puzzle.rows::<i32>().unwrap(), [vec![1, 2, 3]]);
+
+        let settings = PuzzleInputSettings {
+            trim: false,
+            ..with_delimiter(Delimiter::Comma)
+        };
+        let Err(PuzzleInputAssetLoaderError::IntegerConversionError { span, .. }) =
+            PuzzleInputAsset::parse("test.aoc", input.into(), &settings)
+        else {
+            panic!("expected the untrimmed ` 2 ` not to parse");
+        };
+        assert_eq!(span, (2, 3).into());
+    }
+
+    #[test]
+    fn test_sections() {
+        let settings = PuzzleInputSettings {
+            sections: true,
+            ..default()
+        };
+        // Blank lines at the start, several in a row, and at the end only separate sections once.
+        let puzzle = parse("\n\n1 2\n3\n\n\n4\n\n", settings);
+        assert_eq!(
+            puzzle.sections::<i32>().unwrap(),
+            [vec![vec![1, 2], vec![3]], vec![vec![4]]]
+        );
+        assert_eq!(puzzle.rows::<i32>().unwrap(), [vec![1, 2], vec![3]]);
+        // Each row can still be traced back to its line.
+        assert_eq!(
+            [puzzle.line(0, 0), puzzle.line(0, 1), puzzle.line(1, 0)],
+            [2, 3, 6]
+        );
+
+        // Without sections, blank lines are empty rows.
+        let puzzle = parse("1\n\n2", PuzzleInputSettings::default());
+        assert_eq!(puzzle.rows::<i32>().unwrap(), [vec![1], vec![], vec![2]]);
+        assert_eq!(puzzle.line(0, 2), 2);
+    }
+
+    #[test]
+    fn test_bad_number() {
+        let input = "1 2\n3 x4\n";
+        let Err(PuzzleInputAssetLoaderError::IntegerConversionError {
+            input: source,
+            span,
+            number_type,
+            ..
+        }) = PuzzleInputAsset::parse("test.aoc", input.into(), &PuzzleInputSettings::default())
+        else {
+            panic!("expected `x4` not to parse");
+        };
+        // The label covers just the bad number, on the second line.
+        assert_eq!(span, (6, 2).into());
+        assert_eq!(&input[6..8], "x4");
+        assert_eq!(number_type, NumberType::I32);
+        assert_eq!(source.name(), "test.aoc");
+    }
+}
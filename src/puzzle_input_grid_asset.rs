@@ -0,0 +1,55 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+};
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::grid::{Grid, RaggedGridError};
+
+#[derive(Asset, TypePath, Debug)]
+pub struct PuzzleInputGridAsset(pub Grid<char>);
+
+#[derive(Default)]
+pub struct PuzzleInputGridAssetLoader;
+
+/// Possible errors that can be produced by [`PuzzleInputGridAssetLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error, Diagnostic)]
+pub enum PuzzleInputGridAssetLoaderError {
+    /// An [IO](std::io) Error
+    #[error("Could not load asset: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A [string conversion](std::string::FromUtf8Error) error
+    #[error("Could not parse utf8 from bytes: {0}")]
+    FromUtf8(#[from] std::string::FromUtf8Error),
+
+    /// The rows aren't all the same width
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Ragged(#[from] RaggedGridError),
+}
+
+// One cell per character, for puzzles laid out on a map.
+impl AssetLoader for PuzzleInputGridAssetLoader {
+    type Asset = PuzzleInputGridAsset;
+    type Settings = ();
+    type Error = PuzzleInputGridAssetLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(PuzzleInputGridAsset(String::from_utf8(bytes)?.parse()?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aoc"]
+    }
+}
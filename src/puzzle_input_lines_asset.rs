@@ -3,21 +3,22 @@ use bevy::{
     prelude::*,
     reflect::TypePath,
 };
+use miette::Diagnostic;
 use thiserror::Error;
 
 #[derive(Asset, TypePath, Debug)]
-pub(crate) struct PuzzleInputLinesAsset {
+pub struct PuzzleInputLinesAsset {
     #[allow(dead_code)]
     pub rows: Vec<String>,
 }
 
 #[derive(Default)]
-pub(crate) struct PuzzleInputLinesAssetLoader;
+pub struct PuzzleInputLinesAssetLoader;
 
 /// Possible errors that can be produced by [`PuzzleInputLinesAssetLoader`]
 #[non_exhaustive]
-#[derive(Debug, Error)]
-pub(crate) enum PuzzleInputLinesAssetLoaderError {
+#[derive(Debug, Error, Diagnostic)]
+pub enum PuzzleInputLinesAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load asset: {0}")]
     Io(#[from] std::io::Error),
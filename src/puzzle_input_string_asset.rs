@@ -4,6 +4,7 @@ use bevy::{
     prelude::*,
     reflect::TypePath,
 };
+use miette::Diagnostic;
 use thiserror::Error;
 
 #[derive(Asset, TypePath, Debug)]
@@ -14,7 +15,7 @@ pub struct PuzzleInputStringAssetLoader;
 
 /// Possible errors that can be produced by [`PuzzleInputStringAssetLoader`]
 #[non_exhaustive]
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Diagnostic)]
 pub enum PuzzleInputStringAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load asset: {0}")]
@@ -0,0 +1,200 @@
+use std::{
+    collections::BTreeMap,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    answer::{Part, PuzzleAnswer},
+    days::Days,
+    failure::PuzzleError,
+    headless::{self, Report},
+    loading::{InputPaths, InputSource},
+    tasks::Progress,
+    timing::{Stage, StageTimed},
+    AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Results>().add_systems(
+        Update,
+        (
+            cancel_stale_solves.run_if(resource_changed::<InputSource>),
+            // Only hand over background results at the menu, where no day is open to show them
+            // on top of its own.
+            finish_background_solves.run_if(in_state(AoCState::Menu)),
+            record,
+        )
+            .chain(),
+    );
+}
+
+/// What a day's solvers last produced.
+#[derive(Clone, Debug, Default)]
+pub struct DayResults {
+    pub answers: HashMap<Part, String>,
+    pub stages: BTreeMap<Stage, Duration>,
+    /// Why the day last failed, if it did.
+    pub error: Option<String>,
+}
+
+/// The latest results for each day, whether it was solved on screen or in the background.
+#[derive(Resource, Default, Debug)]
+pub struct Results {
+    days: HashMap<u8, DayResults>,
+    solving: HashSet<u8>,
+}
+
+impl Results {
+    pub fn get(&self, day: u8) -> Option<&DayResults> {
+        self.days.get(&day)
+    }
+
+    /// Whether the day is being solved in the background.
+    pub fn is_solving(&self, day: u8) -> bool {
+        self.solving.contains(&day)
+    }
+
+    /// Forgets every result, e.g. because they were for the other set of inputs. Days still being
+    /// solved in the background for those inputs are cancelled once the source changes.
+    pub fn clear(&mut self) {
+        self.days.clear();
+        self.solving.clear();
+    }
+}
+
+// Every day sends its process timing first, even if it fails, so that marks the start of a new
+// set of results.
+fn record(
+    mut timed: EventReader<StageTimed>,
+    mut errors: EventReader<PuzzleError>,
+    mut answers: EventReader<PuzzleAnswer>,
+    mut results: ResMut<Results>,
+) {
+    for timed in timed.read() {
+        let day = results.days.entry(timed.day).or_default();
+        if timed.stage == Stage::Process {
+            *day = DayResults::default();
+        }
+        day.stages.insert(timed.stage, timed.elapsed);
+    }
+    for error in errors.read() {
+        results.days.entry(error.day).or_default().error = Some(error.report.to_string());
+    }
+    for answer in answers.read() {
+        results
+            .days
+            .entry(answer.day)
+            .or_default()
+            .answers
+            .insert(answer.part, answer.value.clone());
+    }
+}
+
+/// A day being solved on another thread, in an app of its own. Despawning it cancels the solve.
+#[derive(Component)]
+pub struct BackgroundSolve {
+    day: u8,
+    source: InputSource,
+    cancel: Progress,
+    handle: Option<JoinHandle<Option<Report>>>,
+}
+
+impl Drop for BackgroundSolve {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Solves every registered day in the background, without a window, so the menu can show their
+/// results without opening each one. Days already being solved are left alone.
+pub fn solve_all(
+    mut commands: Commands,
+    days: Res<Days>,
+    source: Res<InputSource>,
+    mut results: ResMut<Results>,
+) {
+    for day in days.iter().map(|day| day.number) {
+        if !results.solving.insert(day) {
+            continue;
+        }
+        let source = *source;
+        let cancel = Progress::default();
+        let shared = cancel.clone();
+        commands.spawn((
+            Name::new(format!("Solve Day {day}")),
+            BackgroundSolve {
+                day,
+                source,
+                cancel,
+                handle: Some(thread::spawn(move || {
+                    headless::solve_unless_cancelled(day, source, InputPaths::default(), &shared)
+                })),
+            },
+        ));
+    }
+}
+
+/// Stops every day being solved in the background, leaving their last results as they were.
+pub fn cancel_all(
+    mut commands: Commands,
+    solves: Query<Entity, With<BackgroundSolve>>,
+    mut results: ResMut<Results>,
+) {
+    for entity in &solves {
+        commands.entity(entity).despawn();
+    }
+    results.solving.clear();
+}
+
+// Cancels background solves for the inputs that were just switched away from, whose answers
+// would be checked against the wrong ones.
+fn cancel_stale_solves(
+    mut commands: Commands,
+    solves: Query<(Entity, &BackgroundSolve)>,
+    source: Res<InputSource>,
+) {
+    for (entity, solve) in &solves {
+        if solve.source != *source {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Passes on what each background solve found as if the day had been solved on screen, so the
+// answers are checked and recorded like any others.
+fn finish_background_solves(
+    mut commands: Commands,
+    mut solves: Query<(Entity, &mut BackgroundSolve)>,
+    mut results: ResMut<Results>,
+    source: Res<InputSource>,
+    mut answers: EventWriter<PuzzleAnswer>,
+    mut errors: EventWriter<PuzzleError>,
+    mut timed: EventWriter<StageTimed>,
+) {
+    for (entity, mut solve) in &mut solves {
+        if !solve.handle.as_ref().is_some_and(JoinHandle::is_finished) {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        // Answers for the other inputs would be checked against these ones' expected answers.
+        if solve.source != *source {
+            continue;
+        }
+        results.solving.remove(&solve.day);
+        match solve.handle.take().map(JoinHandle::join) {
+            Some(Ok(Some(report))) => {
+                timed.send_batch(report.stages);
+                errors.send_batch(report.errors);
+                answers.send_batch(report.answers);
+            }
+            Some(Ok(None)) | None => {}
+            Some(Err(_)) => error!("Solving day {} in the background panicked", solve.day),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{asset::ron, prelude::*, window::PrimaryWindow};
+use miette::{Diagnostic, Report};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    days::Days,
+    loading::{BrokenInputs, InputSource},
+    simulation::SimulationClock,
+    AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, apply)
+        .add_systems(OnEnter(AoCState::Menu), resume.run_if(run_once))
+        .add_systems(Update, (sync, save).chain());
+}
+
+// Saving waits until nothing has changed for this long, so dragging the window around doesn't
+// write the file every frame.
+const SAVE_DELAY_SECS: f32 = 0.5;
+
+/// Everything about the app that is kept between runs, in `settings.ron` in the user's config
+/// directory.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// The day that was open when the app closed, which is opened again once the inputs have
+    /// loaded. `None` if it was at the menu.
+    pub day: Option<u8>,
+    pub source: InputSource,
+    /// Steps per second for the day simulations.
+    pub speed: f32,
+    /// The size of the window, in logical pixels.
+    pub window: Vec2,
+    /// Whether to solve every day in the background on startup, as `--solve-all` does.
+    pub solve_all: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            day: None,
+            source: InputSource::default(),
+            speed: SimulationClock::default().speed,
+            window: Vec2::new(1280., 720.),
+            solve_all: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Where the settings are kept, falling back to the working directory on platforms without a
+    /// config directory.
+    pub fn path() -> PathBuf {
+        dirs::config_dir()
+            .map(|dir| dir.join(env!("CARGO_PKG_NAME")))
+            .unwrap_or_default()
+            .join("settings.ron")
+    }
+
+    /// Reads the settings from `path`, using the defaults if it doesn't exist. Settings missing
+    /// from the file, e.g. ones added since it was written, take their default values.
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        match fs::read_to_string(path) {
+            Ok(settings) => Ok(ron::from_str(&settings)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let settings = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        Ok(fs::write(path, settings)?)
+    }
+
+    /// Loads the settings from [`Self::path`], reporting any problem and falling back to the
+    /// defaults rather than refusing to start.
+    pub fn load_or_default() -> Self {
+        Self::load(&Self::path()).unwrap_or_else(|e| {
+            eprintln!("{:?}", Report::new(e));
+            Self::default()
+        })
+    }
+}
+
+/// Possible errors when loading or saving [`Settings`].
+#[derive(Debug, Error, Diagnostic)]
+pub enum SettingsError {
+    #[error("Could not access the settings: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Could not read the settings: {0}")]
+    #[diagnostic(
+        code(aoc::settings::parse),
+        help("fix or delete the settings file to go back to the defaults")
+    )]
+    Parse(#[from] ron::error::SpannedError),
+
+    #[error("Could not write the settings: {0}")]
+    Write(#[from] ron::Error),
+}
+
+// The window size and input source are used while building the app, but the clock only exists
+// once the plugins are in.
+fn apply(settings: Res<Settings>, mut clock: ResMut<SimulationClock>) {
+    clock.set_speed(settings.speed);
+}
+
+// Goes back to the day that was open last time, instead of stopping at the menu after loading.
+fn resume(
+    settings: Res<Settings>,
+    days: Res<Days>,
+    broken: Res<BrokenInputs>,
+    mut next_state: ResMut<NextState<AoCState>>,
+) {
+    let Some(day) = settings.day else {
+        return;
+    };
+    if days.get(day).is_some() && !broken.contains(day) {
+        next_state.set(AoCState::Day(day));
+    }
+}
+
+// Keeps the settings in step with the app.
+fn sync(
+    mut settings: ResMut<Settings>,
+    state: Res<State<AoCState>>,
+    source: Res<InputSource>,
+    clock: Res<SimulationClock>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+) {
+    let mut new = settings.clone();
+    match *state.get() {
+        AoCState::Menu => new.day = None,
+        AoCState::Day(day) => new.day = Some(day),
+        // Still on the way to wherever the app was left.
+        AoCState::Loading | AoCState::LoadingFailed => {}
+    }
+    // Only keep inputs switched to in the app, not ones picked for a single run with `--sample`.
+    if source.is_changed() && !source.is_added() {
+        new.source = *source;
+    }
+    new.speed = clock.speed;
+    if let Some(window) = window {
+        new.window = Vec2::new(window.resolution.width(), window.resolution.height());
+    }
+    settings.set_if_neq(new);
+}
+
+fn save(settings: Res<Settings>, time: Res<Time>, mut waited: Local<Option<f32>>) {
+    if settings.is_changed() && !settings.is_added() {
+        *waited = Some(0.);
+    }
+    let Some(waited_secs) = &mut *waited else {
+        return;
+    };
+    *waited_secs += time.delta_secs();
+    if *waited_secs < SAVE_DELAY_SECS {
+        return;
+    }
+    *waited = None;
+
+    let path = Settings::path();
+    if let Err(e) = settings.save(&path) {
+        error!("Could not save {}: {:?}", path.display(), Report::new(e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_settings_round_trip() -> Result<(), SettingsError> {
+        let dir = TempDir::new("settings");
+        let path = dir.join("settings.ron");
+        assert_eq!(Settings::load(&path)?, Settings::default());
+
+        let settings = Settings {
+            day: Some(6),
+            source: InputSource::Sample,
+            speed: 256.,
+            window: Vec2::new(800., 600.),
+            solve_all: true,
+        };
+        settings.save(&path)?;
+        assert_eq!(Settings::load(&path)?, settings);
+
+        // Older files without every setting still load.
+        fs::write(&path, "(day: Some(3))")?;
+        let loaded = Settings::load(&path)?;
+        assert_eq!(loaded.day, Some(3));
+        assert_eq!(loaded.speed, Settings::default().speed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_switching_inputs_in_the_app_is_kept() {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_state::<AoCState>()
+            .init_resource::<SimulationClock>()
+            .insert_resource(Settings {
+                source: InputSource::Real,
+                ..default()
+            })
+            // As `--sample` would.
+            .insert_resource(InputSource::Sample)
+            .add_systems(Update, sync);
+        app.update();
+        assert_eq!(app.world().resource::<Settings>().source, InputSource::Real);
+
+        // As the menu's toggle would.
+        *app.world_mut().resource_mut::<InputSource>() = InputSource::Sample;
+        app.update();
+        assert_eq!(
+            app.world().resource::<Settings>().source,
+            InputSource::Sample
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{days::DayRoot, ui};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<SimulationClock>()
+        .init_resource::<Simulated>()
+        .add_systems(
+            Update,
+            (tick.before(SimulationStep), spawn_controls, update_controls),
+        );
+}
+
+const MIN_SPEED: f32 = 1.;
+const MAX_SPEED: f32 = 1_048_576.;
+
+/// How fast the day simulations run. Shared by every day, so the speed is kept when switching
+/// between them.
+#[derive(Resource, Debug)]
+pub struct SimulationClock {
+    pub playing: bool,
+    /// Steps per second while playing.
+    pub speed: f32,
+    // Single steps asked for since the last frame, which run even while paused.
+    queued: u32,
+    // The part of a step left over from earlier frames.
+    carry: f32,
+    steps: u32,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self {
+            playing: true,
+            speed: 64.,
+            queued: 0,
+            carry: 0.,
+            steps: 0,
+        }
+    }
+}
+
+impl SimulationClock {
+    /// How many steps the simulations should take this frame.
+    pub const fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Takes a single step on the next frame, whether or not the clock is playing.
+    pub const fn step(&mut self) {
+        self.queued += 1;
+    }
+
+    /// Sets the speed, keeping it within the range the controls can reach.
+    pub const fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.).max(MIN_SPEED);
+    }
+
+    fn tick(&mut self, delta: f32) {
+        self.steps = std::mem::take(&mut self.queued);
+        if self.playing {
+            self.carry += self.speed * delta;
+            let whole = self.carry.floor();
+            self.carry -= whole;
+            self.steps += whole as u32;
+        }
+    }
+}
+
+/// Where each day's simulation systems run, once the [`SimulationClock`] knows how many steps
+/// they should take this frame.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SimulationStep;
+
+/// The days with a simulation, which get controls for the [`SimulationClock`].
+#[derive(Resource, Default, Debug)]
+pub struct Simulated(pub HashSet<u8>);
+
+fn tick(mut clock: ResMut<SimulationClock>, time: Res<Time>) {
+    clock.tick(time.delta_secs());
+}
+
+#[derive(Component, Debug)]
+struct PlayPauseLabel;
+
+#[derive(Component, Debug)]
+struct SpeedLabel;
+
+// Adds the controls to a simulated day whenever it is opened or solved again.
+fn spawn_controls(
+    mut commands: Commands,
+    roots: Query<(Entity, &DayRoot), Added<DayRoot>>,
+    simulated: Res<Simulated>,
+    mut clock: ResMut<SimulationClock>,
+) {
+    for (entity, root) in &roots {
+        if !simulated.0.contains(&root.0) {
+            continue;
+        }
+        // Start the new simulation from its first step.
+        clock.queued = 0;
+        clock.carry = 0.;
+
+        commands.entity(entity).with_children(|p| {
+            p.spawn((
+                Name::new("Simulation Controls"),
+                Node {
+                    align_items: AlignItems::Center,
+                    bottom: Val::Px(10.),
+                    column_gap: Val::Px(5.),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                ui::spawn_button(
+                    p,
+                    "Play/Pause",
+                    PlayPauseLabel,
+                    |_ev: Trigger<Pointer<Click>>, mut clock: ResMut<SimulationClock>| {
+                        clock.playing = !clock.playing;
+                    },
+                );
+                ui::spawn_button(
+                    p,
+                    "Step",
+                    (),
+                    |_ev: Trigger<Pointer<Click>>, mut clock: ResMut<SimulationClock>| {
+                        clock.playing = false;
+                        clock.step();
+                    },
+                );
+                ui::spawn_button(
+                    p,
+                    "Slower",
+                    (),
+                    |_ev: Trigger<Pointer<Click>>, mut clock: ResMut<SimulationClock>| {
+                        clock.slower();
+                    },
+                );
+                ui::spawn_button(
+                    p,
+                    "Faster",
+                    (),
+                    |_ev: Trigger<Pointer<Click>>, mut clock: ResMut<SimulationClock>| {
+                        clock.faster();
+                    },
+                );
+                p.spawn((
+                    SpeedLabel,
+                    Text::default(),
+                    TextFont {
+                        font_size: 14.,
+                        ..default()
+                    },
+                ));
+            });
+        });
+    }
+}
+
+fn update_controls(
+    clock: Res<SimulationClock>,
+    mut play_pause: Query<&mut Text, (With<PlayPauseLabel>, Without<SpeedLabel>)>,
+    mut speed: Query<&mut Text, With<SpeedLabel>>,
+) {
+    for mut text in &mut play_pause {
+        text.0 = if clock.playing { "Pause" } else { "Play" }.into();
+    }
+    for mut text in &mut speed {
+        text.0 = format!("{} steps/s", clock.speed);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::{Duration, SystemTime},
+};
+
+use bevy::{asset::ron, prelude::*};
+use miette::{Diagnostic, Report};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    answer::Part,
+    client::{Client, ClientError, NoSessionError},
+    headless,
+};
+
+/// Sends an answer without opening a window, solving the day first if no answer is given.
+pub fn run(day: u8, part: Part, answer: Option<String>, base_url: Option<String>) -> AppExit {
+    let Some(answer) = answer.or_else(|| headless::answer(day, part)) else {
+        eprintln!("No answer to submit for day {day} part {part:?}");
+        return AppExit::error();
+    };
+
+    let result = Submitter::from_env().and_then(|mut submitter| {
+        if let Some(base_url) = base_url {
+            submitter.client.base_url = base_url;
+        }
+        submitter.submit(day, part, &answer)
+    });
+    match result {
+        Ok(outcome) => {
+            println!("Day {day} part {part:?}: {answer}");
+            println!("{outcome}");
+            if outcome == Outcome::Right {
+                AppExit::Success
+            } else {
+                AppExit::error()
+            }
+        }
+        Err(e) => {
+            eprintln!("{:?}", Report::new(e));
+            AppExit::error()
+        }
+    }
+}
+
+/// What Advent of Code made of a submitted answer.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Right,
+    Wrong {
+        hint: Option<Hint>,
+        /// How long until another answer will be accepted.
+        wait: Option<Duration>,
+    },
+    /// The previous answer was too recent for this one to be checked.
+    TooSoon {
+        wait: Duration,
+    },
+    /// The part has already been solved, or isn't unlocked yet.
+    WrongLevel,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Outcome {
+    /// Reads the outcome from the page Advent of Code responds with.
+    pub fn parse(page: &str) -> Option<Self> {
+        static WRONG_WAIT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"wait (one|\d+) minutes? before trying again").unwrap());
+        static TOO_SOON_WAIT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap());
+
+        if page.contains("That's the right answer") {
+            Some(Self::Right)
+        } else if page.contains("That's not the right answer") {
+            let hint = if page.contains("your answer is too high") {
+                Some(Hint::TooHigh)
+            } else if page.contains("your answer is too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            };
+            let wait = WRONG_WAIT.captures(page).map(|caps| {
+                let minutes = caps[1].parse().unwrap_or(1);
+                Duration::from_secs(minutes * 60)
+            });
+            Some(Self::Wrong { hint, wait })
+        } else if page.contains("You gave an answer too recently") {
+            let caps = TOO_SOON_WAIT.captures(page)?;
+            let minutes: u64 = caps.get(1).map_or(Ok(0), |m| m.as_str().parse()).ok()?;
+            let seconds: u64 = caps[2].parse().ok()?;
+            Some(Self::TooSoon {
+                wait: Duration::from_secs(minutes * 60 + seconds),
+            })
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Self::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    const fn wait(self) -> Option<Duration> {
+        match self {
+            Self::Wrong { wait, .. } => wait,
+            Self::TooSoon { wait } => Some(wait),
+            Self::Right | Self::WrongLevel => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Right => write!(f, "That's the right answer"),
+            Self::Wrong { hint, wait } => {
+                write!(f, "That's not the right answer")?;
+                match hint {
+                    Some(Hint::TooHigh) => write!(f, ", it's too high")?,
+                    Some(Hint::TooLow) => write!(f, ", it's too low")?,
+                    None => {}
+                }
+                if let Some(wait) = wait {
+                    write!(f, ". Wait {}s before trying again", wait.as_secs())?;
+                }
+                Ok(())
+            }
+            Self::TooSoon { wait } => write!(
+                f,
+                "An answer was sent too recently, wait {}s before trying again",
+                wait.as_secs()
+            ),
+            Self::WrongLevel => write!(f, "That part is already solved or isn't unlocked yet"),
+        }
+    }
+}
+
+/// An answer that has been sent, and what Advent of Code made of it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Guess {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Every answer sent so far, kept so that none are sent twice and waits are respected between
+/// runs.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Guesses {
+    guesses: Vec<Guess>,
+    /// No more answers will be accepted until then.
+    wait_until: Option<SystemTime>,
+}
+
+impl Guesses {
+    pub fn load(path: &Path) -> Result<Self, SubmitError> {
+        match fs::read_to_string(path) {
+            Ok(guesses) => Ok(ron::from_str(&guesses)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SubmitError> {
+        let guesses = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        Ok(fs::write(path, guesses)?)
+    }
+
+    /// Checks that `answer` is worth sending at `now`, given the answers sent before it.
+    pub fn check(
+        &self,
+        day: u8,
+        part: Part,
+        answer: &str,
+        now: SystemTime,
+    ) -> Result<(), SubmitError> {
+        let previous = || {
+            self.guesses
+                .iter()
+                .filter(move |guess| guess.day == day && guess.part == part)
+        };
+
+        if let Some(right) = previous().find(|guess| guess.outcome == Outcome::Right) {
+            return Err(SubmitError::Solved {
+                answer: right.answer.clone(),
+            });
+        }
+        if let Some(guess) = previous().find(|guess| guess.answer == answer) {
+            return Err(SubmitError::AlreadyGuessed {
+                outcome: guess.outcome,
+            });
+        }
+        // A number at or past one that was too high or too low can't be right either.
+        if let Ok(value) = answer.parse::<i64>() {
+            for guess in previous() {
+                let Ok(guessed) = guess.answer.parse::<i64>() else {
+                    continue;
+                };
+                let ruled_out = match guess.outcome {
+                    Outcome::Wrong {
+                        hint: Some(Hint::TooHigh),
+                        ..
+                    } => value >= guessed,
+                    Outcome::Wrong {
+                        hint: Some(Hint::TooLow),
+                        ..
+                    } => value <= guessed,
+                    _ => false,
+                };
+                if ruled_out {
+                    return Err(SubmitError::RuledOut {
+                        guess: guess.answer.clone(),
+                        outcome: guess.outcome,
+                    });
+                }
+            }
+        }
+        if let Some(wait) = self
+            .wait_until
+            .and_then(|until| until.duration_since(now).ok())
+        {
+            return Err(SubmitError::RateLimited { wait });
+        }
+        Ok(())
+    }
+
+    /// Remembers an answer that was sent at `now`. Only answers that were checked are kept, but
+    /// any wait is respected either way.
+    pub fn record(&mut self, day: u8, part: Part, answer: &str, outcome: Outcome, now: SystemTime) {
+        if let Some(wait) = outcome.wait() {
+            self.wait_until = Some(now + wait);
+        }
+        if matches!(outcome, Outcome::Right | Outcome::Wrong { .. }) {
+            self.guesses.push(Guess {
+                day,
+                part,
+                answer: answer.into(),
+                outcome,
+            });
+        }
+    }
+}
+
+/// Sends answers to Advent of Code, keeping track of them in a local file.
+pub struct Submitter {
+    pub client: Client,
+    /// Where the [`Guesses`] are kept.
+    pub guesses: PathBuf,
+}
+
+impl Submitter {
+    /// Keeps guesses in `.aoc-guesses.ron` in the working directory, using the account from
+    /// [`Client::from_env`].
+    pub fn from_env() -> Result<Self, SubmitError> {
+        Ok(Self {
+            client: Client::from_env()?,
+            guesses: ".aoc-guesses.ron".into(),
+        })
+    }
+
+    /// Sends an answer, unless it has been sent before, is ruled out by an earlier one or would
+    /// be sent before Advent of Code is willing to accept it.
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Outcome, SubmitError> {
+        let mut guesses = Guesses::load(&self.guesses)?;
+        guesses.check(day, part, answer, SystemTime::now())?;
+
+        let level = match part {
+            Part::A => "1",
+            Part::B => "2",
+        };
+        let page = self.client.post_form(
+            &format!("day/{day}/answer"),
+            &[("level", level), ("answer", answer)],
+        )?;
+        let outcome = Outcome::parse(&page).ok_or(SubmitError::UnexpectedResponse)?;
+
+        guesses.record(day, part, answer, outcome, SystemTime::now());
+        guesses.save(&self.guesses)?;
+        Ok(outcome)
+    }
+}
+
+/// Possible errors when submitting an answer. Most of them mean the answer was never sent.
+#[derive(Debug, Error, Diagnostic)]
+pub enum SubmitError {
+    #[error("This part was already solved with {answer}")]
+    #[diagnostic(code(aoc::submit::solved))]
+    Solved { answer: String },
+
+    #[error("This answer was already sent: {outcome}")]
+    #[diagnostic(code(aoc::submit::guessed))]
+    AlreadyGuessed { outcome: Outcome },
+
+    #[error("This answer can't be right, because {guess} was sent before: {outcome}")]
+    #[diagnostic(code(aoc::submit::ruled_out))]
+    RuledOut { guess: String, outcome: Outcome },
+
+    #[error("Advent of Code won't accept another answer for {}s", .wait.as_secs())]
+    #[diagnostic(code(aoc::submit::rate_limited))]
+    RateLimited { wait: Duration },
+
+    #[error("Could not tell whether the answer was right from the response")]
+    #[diagnostic(
+        code(aoc::submit::unexpected),
+        help("the answer was sent, so check the puzzle page before sending another")
+    )]
+    UnexpectedResponse,
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    NoSession(#[from] NoSessionError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Client(#[from] ClientError),
+
+    #[error("Could not read the previous guesses: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+
+    #[error("Could not write the guesses: {0}")]
+    RonWrite(#[from] ron::Error),
+
+    #[error("Could not access the guesses: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::mock, temp_dir::TempDir};
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+        If you're stuck, make sure you're using the full input data. Please wait one minute \
+        before trying again. <a href=\"/2024/day/1\">[Return to Day 1]</a></p></article>";
+
+    #[test]
+    fn test_parse_outcome() {
+        assert_eq!(
+            Outcome::parse(TOO_HIGH),
+            Some(Outcome::Wrong {
+                hint: Some(Hint::TooHigh),
+                wait: Some(Duration::from_secs(60)),
+            })
+        );
+        assert_eq!(
+            Outcome::parse("<p>That's the right answer! You are one gold star closer.</p>"),
+            Some(Outcome::Right)
+        );
+        assert_eq!(
+            Outcome::parse("<p>You gave an answer too recently. You have 4m 32s left to wait.</p>"),
+            Some(Outcome::TooSoon {
+                wait: Duration::from_secs(4 * 60 + 32),
+            })
+        );
+        assert_eq!(Outcome::parse("<p>Something else</p>"), None);
+    }
+
+    #[test]
+    fn test_submit_records_guesses() {
+        let (client, server) = mock::serve_once(TOO_HIGH);
+        let dir = TempDir::new("submit");
+        let submitter = Submitter {
+            client,
+            guesses: dir.join("guesses.ron"),
+        };
+
+        let outcome = submitter.submit(1, Part::B, "100").unwrap();
+        assert!(matches!(
+            outcome,
+            Outcome::Wrong {
+                hint: Some(Hint::TooHigh),
+                ..
+            }
+        ));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2024/day/1/answer "));
+        assert!(request.ends_with("level=2&answer=100"));
+
+        // The stand-in server is gone, so these would fail if they tried to send anything.
+        assert!(matches!(
+            submitter.submit(1, Part::B, "100"),
+            Err(SubmitError::AlreadyGuessed { .. })
+        ));
+        assert!(matches!(
+            submitter.submit(1, Part::B, "150"),
+            Err(SubmitError::RuledOut { .. })
+        ));
+        assert!(matches!(
+            submitter.submit(1, Part::B, "50"),
+            Err(SubmitError::RateLimited { .. })
+        ));
+    }
+}
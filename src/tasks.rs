@@ -0,0 +1,269 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use bevy::{
+    color::palettes::css::{DIM_GRAY, GOLD},
+    ecs::system::SystemParam,
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+
+use crate::{
+    answer::{AnswerPanel, Part, PuzzleAnswer},
+    timing::{Stage, StageTimed},
+    AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (finish_solves, spawn_progress_bars, show_progress).chain(),
+    );
+}
+
+/// How far a solver running in the background has got, shared between it and the app. Dropping
+/// the [`Solve`] it belongs to cancels it.
+#[derive(Clone, Default, Debug)]
+pub struct Progress(Arc<ProgressState>);
+
+#[derive(Default, Debug)]
+struct ProgressState {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    /// Sets how many steps the solver expects to take.
+    pub fn start(&self, total: usize) {
+        self.0.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Marks another step as done, returning `false` if the solver should stop instead of
+    /// carrying on.
+    pub fn step(&self) -> bool {
+        self.0.done.fetch_add(1, Ordering::Relaxed);
+        !self.is_cancelled()
+    }
+
+    /// The fraction of the steps done so far, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        let total = self.0.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.;
+        }
+        (self.0.done.load(Ordering::Relaxed) as f32 / total as f32).min(1.)
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A part being solved on the [`AsyncComputeTaskPool`]. Scoped to its day's state, so leaving the
+/// day cancels it.
+#[derive(Component)]
+pub struct Solve {
+    day: u8,
+    part: Part,
+    progress: Progress,
+    started: Instant,
+    /// Finishes with the answer, or `None` if the solver was cancelled.
+    task: Task<Option<String>>,
+}
+
+impl Solve {
+    pub const fn stage(&self) -> (u8, Stage) {
+        (
+            self.day,
+            match self.part {
+                Part::A => Stage::SolveA,
+                Part::B => Stage::SolveB,
+            },
+        )
+    }
+}
+
+// The task itself can't be stopped part way through a step, so tell the solver to give up.
+impl Drop for Solve {
+    fn drop(&mut self) {
+        self.progress.cancel();
+    }
+}
+
+/// Solves parts in the background, for solvers too slow to run without freezing the window.
+/// Their answers are sent as [`PuzzleAnswer`]s once they finish, with the part's
+/// [`StageTimed`] alongside.
+#[derive(SystemParam)]
+pub struct Solvers<'w, 's> {
+    commands: Commands<'w, 's>,
+}
+
+impl Solvers<'_, '_> {
+    /// Starts solving a part with `solve`, which should update the [`Progress`] it is given as it
+    /// goes and return `None` if told to stop.
+    pub fn spawn<T: ToString>(
+        &mut self,
+        day: u8,
+        part: Part,
+        solve: impl FnOnce(&Progress) -> Option<T> + Send + 'static,
+    ) {
+        let progress = Progress::default();
+        let shared = progress.clone();
+        let task = AsyncComputeTaskPool::get()
+            .spawn(async move { solve(&shared).map(|answer| answer.to_string()) });
+        self.commands.spawn((
+            Name::new(format!("Day {day} part {part:?} Solve")),
+            Solve {
+                day,
+                part,
+                progress,
+                started: Instant::now(),
+                task,
+            },
+            StateScoped(AoCState::Day(day)),
+        ));
+    }
+}
+
+fn finish_solves(
+    mut commands: Commands,
+    mut solves: Query<(Entity, &mut Solve)>,
+    mut answers: EventWriter<PuzzleAnswer>,
+    mut timed: EventWriter<StageTimed>,
+) {
+    for (entity, mut solve) in &mut solves {
+        if !solve.task.is_finished() {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let Some(value) = block_on(&mut solve.task) else {
+            continue;
+        };
+        let elapsed = solve.started.elapsed();
+        let (day, stage) = solve.stage();
+        timed.send(StageTimed {
+            day,
+            stage,
+            elapsed,
+        });
+        answers.send(PuzzleAnswer {
+            day,
+            part: solve.part,
+            value,
+            elapsed,
+        });
+    }
+}
+
+/// A bar under a day's answers showing how far a [`Solve`] has got.
+#[derive(Component, Debug)]
+struct ProgressBar(Entity);
+
+/// The part of a [`ProgressBar`] that fills up.
+#[derive(Component, Debug)]
+struct ProgressFill;
+
+fn spawn_progress_bars(
+    mut commands: Commands,
+    solves: Query<(Entity, &Solve), Added<Solve>>,
+    panels: Query<(Entity, &AnswerPanel)>,
+) {
+    for (solve_entity, solve) in &solves {
+        for (entity, panel) in &panels {
+            if panel.0 != solve.day {
+                continue;
+            }
+            commands.entity(entity).with_children(|p| {
+                p.spawn((
+                    Name::new("Progress Bar"),
+                    ProgressBar(solve_entity),
+                    Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(5.),
+                        ..default()
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn((
+                        Text::new(format!("Part {:?}", solve.part)),
+                        TextFont {
+                            font_size: 14.,
+                            ..default()
+                        },
+                    ));
+                    p.spawn((
+                        BackgroundColor(DIM_GRAY.into()),
+                        Node {
+                            height: Val::Px(10.),
+                            width: Val::Px(200.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            ProgressFill,
+                            BackgroundColor(GOLD.into()),
+                            Node {
+                                height: Val::Percent(100.),
+                                width: Val::Percent(0.),
+                                ..default()
+                            },
+                        ));
+                    });
+                });
+            });
+        }
+    }
+}
+
+// Fills each bar up as its solver goes, and removes it once the answer is in.
+fn show_progress(
+    mut commands: Commands,
+    bars: Query<(Entity, &ProgressBar)>,
+    solves: Query<&Solve>,
+    children: Query<&Children>,
+    mut fills: Query<&mut Node, With<ProgressFill>>,
+) {
+    for (entity, bar) in &bars {
+        let Ok(solve) = solves.get(bar.0) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        for descendant in children.iter_descendants(entity) {
+            if let Ok(mut fill) = fills.get_mut(descendant) {
+                fill.width = Val::Percent(solve.progress.fraction() * 100.);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::default();
+        assert_eq!(progress.fraction(), 0.);
+
+        progress.start(4);
+        assert!(progress.step());
+        assert_eq!(progress.fraction(), 0.25);
+
+        // Both ends see the cancellation, as the solver holds a clone.
+        let shared = progress.clone();
+        progress.cancel();
+        assert!(shared.is_cancelled());
+        assert!(!shared.step());
+    }
+}
@@ -0,0 +1,37 @@
+//! Scratch directories for tests that read and write real files.
+
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// An empty directory of its own under the system's temp directory, removed along with
+/// everything in it when dropped, so it's cleaned up even if the test fails.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps each test's files apart, and the process id each test run's.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("aoc-{name}-{}", process::id()));
+        // Anything left behind by a run that was killed before it could clean up.
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+
+use crate::export::{Cell, Frame};
+
+const RESET: &str = "\x1b[0m";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+// What the terminal shows for a cell without any `Frame::text`.
+const fn glyph(cell: Cell) -> char {
+    match cell {
+        Cell::Empty => '.',
+        Cell::Wall => '#',
+        Cell::Visited => 'X',
+        Cell::Match => '*',
+        Cell::Marker => '@',
+    }
+}
+
+/// Draws a frame as lines of text, with each cell in its colour unless `color` is off. Empty cells
+/// are left in the terminal's own colour, so they read as background on light and dark themes.
+pub fn render(frame: &Frame, color: bool) -> String {
+    let mut out = String::new();
+    for (y, row) in frame.cells.rows().enumerate() {
+        let mut current = Cell::Empty;
+        for (x, cell) in row.iter().enumerate() {
+            if color && *cell != current {
+                if *cell == Cell::Empty {
+                    out.push_str(RESET);
+                } else {
+                    let [r, g, b, _] = cell.color().to_u8_array();
+                    let _ = write!(out, "\x1b[38;2;{r};{g};{b}m");
+                }
+                current = *cell;
+            }
+            let pos = IVec2::new(x as i32, y as i32);
+            let text = frame.text.as_ref().and_then(|text| text.get(pos).copied());
+            out.push(text.unwrap_or_else(|| glyph(*cell)));
+        }
+        if current != Cell::Empty {
+            out.push_str(RESET);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Plays frames in place, `fps` a second. Rather than clearing the screen, each frame moves the
+/// cursor back up over the last one, which any ANSI terminal understands, including over SSH.
+/// Frames taller than the terminal scroll instead.
+pub fn play(frames: &[Frame], fps: u32, out: &mut impl Write) -> io::Result<()> {
+    let delay = Duration::from_secs(1) / fps.max(1);
+    write!(out, "{HIDE_CURSOR}")?;
+    let mut height = 0;
+    for (i, frame) in frames.iter().enumerate() {
+        if height > 0 {
+            write!(out, "\x1b[{height}A\r")?;
+        }
+        out.write_all(render(frame, true).as_bytes())?;
+        out.flush()?;
+        height = frame.cells.height();
+        if i + 1 < frames.len() {
+            thread::sleep(delay);
+        }
+    }
+    write!(out, "{SHOW_CURSOR}")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export;
+
+    #[test]
+    fn test_render() {
+        let mut frame = export::blank(IRect::new(0, 0, 2, 1));
+        frame[IVec2::new(1, 0)] = Cell::Wall;
+        frame[IVec2::new(2, 1)] = Cell::Marker;
+        assert_eq!(render(&frame, false), ".#.\n..@\n");
+
+        let [r, g, b, _] = Cell::Wall.color().to_u8_array();
+        let first = render(&frame, true).lines().next().unwrap().to_owned();
+        assert_eq!(first, format!(".\x1b[38;2;{r};{g};{b}m#{RESET}."));
+    }
+
+    #[test]
+    fn test_play_moves_back_over_last_frame() -> io::Result<()> {
+        let frames = vec![export::blank(IRect::new(0, 0, 1, 2)); 3];
+        let mut out = vec![];
+        play(&frames, 1000, &mut out)?;
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("\x1b[3A\r").count(), 2);
+        assert!(out.starts_with(HIDE_CURSOR) && out.ends_with(SHOW_CURSOR));
+        Ok(())
+    }
+}
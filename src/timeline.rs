@@ -0,0 +1,372 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    color::palettes::css::{DIM_GRAY, GOLD, GREEN},
+    prelude::*,
+};
+use miette::{Diagnostic, Report};
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+use crate::{days::DayRoot, simulation::SimulationClock, ui};
+
+// Seeking replays at most this fraction of a timeline, at the cost of keeping this many copies of
+// the state.
+const MAX_CHECKPOINTS: usize = 32;
+
+// How finely the scrubber divides the timeline.
+const SEGMENTS: usize = 100;
+
+// Bumped whenever the file layout changes, so old files are rejected rather than misread.
+const FILE_VERSION: u32 = 1;
+
+/// The state of a simulation, which it moves through one recorded step at a time.
+pub trait Replay: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+    type Step: Clone + Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    fn apply(&mut self, step: &Self::Step);
+}
+
+/// Every step a simulation took, and the state it was in after some number of them.
+#[derive(Component)]
+pub struct Timeline<S: Replay> {
+    start: S,
+    steps: Vec<S::Step>,
+    // The state after every `interval` steps, so seeking doesn't have to replay from the start.
+    checkpoints: Vec<S>,
+    interval: usize,
+    current: S,
+    position: usize,
+}
+
+impl<S: Replay> Timeline<S> {
+    pub fn new(start: S, steps: Vec<S::Step>) -> Self {
+        let interval = steps.len().div_ceil(MAX_CHECKPOINTS).max(1);
+        let mut checkpoints = vec![];
+        let mut state = start.clone();
+        for (i, step) in steps.iter().enumerate() {
+            state.apply(step);
+            if (i + 1) % interval == 0 {
+                checkpoints.push(state.clone());
+            }
+        }
+        Self {
+            current: start.clone(),
+            start,
+            steps,
+            checkpoints,
+            interval,
+            position: 0,
+        }
+    }
+
+    /// The state after [`Self::position`] steps.
+    pub const fn current(&self) -> &S {
+        &self.current
+    }
+
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// How many steps were recorded.
+    pub const fn steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Takes the next step, returning `false` if there are none left.
+    pub fn step_forward(&mut self) -> bool {
+        let Some(step) = self.steps.get(self.position) else {
+            return false;
+        };
+        self.current.apply(step);
+        self.position += 1;
+        true
+    }
+
+    /// Moves to the state after `position` steps, or the last state if there aren't that many.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.steps.len());
+        if position < self.position || position - self.position > self.interval {
+            let intervals = position / self.interval;
+            (self.current, self.position) = match intervals
+                .checked_sub(1)
+                .and_then(|i| self.checkpoints.get(i))
+            {
+                Some(state) => (state.clone(), intervals * self.interval),
+                None => (self.start.clone(), 0),
+            };
+        }
+        while self.position < position {
+            self.step_forward();
+        }
+    }
+
+    /// Writes the timeline to `timelines/day{day}.timeline`, returning where it was written.
+    pub fn save(&self, day: u8) -> Result<PathBuf, TimelineError> {
+        let path = path(day);
+        self.write(day, &path)?;
+        Ok(path)
+    }
+
+    /// Reads a timeline written by [`Self::save`], without solving the day again.
+    pub fn load(day: u8) -> Result<Self, TimelineError> {
+        Self::read(day, path(day))
+    }
+
+    fn write(&self, day: u8, path: &Path) -> Result<(), TimelineError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let bytes = bincode::serialize(&(FILE_VERSION, day, &self.start, &self.steps))?;
+        Ok(fs::write(path, bytes)?)
+    }
+
+    fn read(day: u8, path: PathBuf) -> Result<Self, TimelineError> {
+        let bytes = fs::read(&path)?;
+        // Check the header on its own first, as the rest may not make sense to this version.
+        let (version, found): (u32, u8) = bincode::deserialize(&bytes)?;
+        if version != FILE_VERSION {
+            return Err(TimelineError::Version { path, version });
+        }
+        if found != day {
+            return Err(TimelineError::WrongDay { path, found });
+        }
+        let (_, _, start, steps): (u32, u8, S, Vec<S::Step>) = bincode::deserialize(&bytes)?;
+        Ok(Self::new(start, steps))
+    }
+}
+
+fn path(day: u8) -> PathBuf {
+    Path::new("timelines").join(format!("day{day}.timeline"))
+}
+
+/// Possible errors when saving or loading a [`Timeline`].
+#[derive(Debug, Error, Diagnostic)]
+pub enum TimelineError {
+    #[error("Could not access the timeline: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not encode the timeline: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[error("{} was saved by an incompatible version (file version {version})", .path.display())]
+    #[diagnostic(code(aoc::timeline::version))]
+    Version { path: PathBuf, version: u32 },
+
+    #[error("{} is a timeline for day {found}", .path.display())]
+    #[diagnostic(code(aoc::timeline::wrong_day))]
+    WrongDay { path: PathBuf, found: u8 },
+}
+
+/// Plays a day's [`Timeline`] forward as fast as the [`SimulationClock`] allows.
+pub fn play<S: Replay>(clock: Res<SimulationClock>, mut timeline: Single<&mut Timeline<S>>) {
+    for _ in 0..clock.steps() {
+        if !timeline.step_forward() {
+            break;
+        }
+    }
+}
+
+/// One part of the scrubber, covering the timeline from `.0` to the next segment.
+#[derive(Component, Debug)]
+pub struct Segment(f32);
+
+/// The scrubber and save/load buttons for a day's [`Timeline`].
+#[derive(Component, Debug)]
+pub struct Scrubber;
+
+/// Builds a system that adds a scrubber, and buttons for saving and loading, under the root of
+/// the day each new [`Timeline`] is spawned in, unless it already has one.
+pub fn spawn_scrubber<S: Replay>(
+    day: u8,
+) -> impl FnMut(
+    Commands,
+    Query<Entity, Added<Timeline<S>>>,
+    Query<&Parent>,
+    Query<Option<&Children>, With<DayRoot>>,
+    Query<(), With<Scrubber>>,
+) {
+    move |mut commands, added, parents, roots, scrubbers| {
+        for timeline in &added {
+            let Some(root) = parents
+                .iter_ancestors(timeline)
+                .find(|entity| roots.contains(*entity))
+            else {
+                continue;
+            };
+            let has_scrubber =
+                roots.get(root).ok().flatten().is_some_and(|children| {
+                    children.iter().any(|child| scrubbers.contains(*child))
+                });
+            if has_scrubber {
+                continue;
+            }
+            commands.entity(root).with_children(|p| {
+                p.spawn((
+                    Name::new("Scrubber"),
+                    Scrubber,
+                    Node {
+                        align_items: AlignItems::Center,
+                        bottom: Val::Px(50.),
+                        column_gap: Val::Px(5.),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                ))
+                .with_children(|p| {
+                    spawn_segments::<S>(p);
+                    ui::spawn_button(
+                        p,
+                        "Save",
+                        (),
+                        move |_ev: Trigger<Pointer<Click>>, timeline: Single<&Timeline<S>>| {
+                            match timeline.save(day) {
+                                Ok(path) => info!("Saved the timeline to {}", path.display()),
+                                Err(e) => error!("{:?}", Report::new(e)),
+                            }
+                        },
+                    );
+                    ui::spawn_button(
+                        p,
+                        "Load",
+                        (),
+                        move |_ev: Trigger<Pointer<Click>>,
+                              mut timeline: Single<&mut Timeline<S>>| {
+                            match Timeline::load(day) {
+                                Ok(loaded) => **timeline = loaded,
+                                Err(e) => error!("{:?}", Report::new(e)),
+                            }
+                        },
+                    );
+                });
+            });
+        }
+    }
+}
+
+// Clicking or dragging across the segments seeks to them, pausing the clock so they stay put.
+fn spawn_segments<S: Replay>(p: &mut ChildBuilder) {
+    p.spawn((
+        BorderColor(GREEN.into()),
+        Node {
+            border: UiRect::all(Val::Px(2.)),
+            height: Val::Px(20.),
+            width: Val::Px(400.),
+            ..default()
+        },
+    ))
+    .with_children(|p| {
+        for i in 0..SEGMENTS {
+            let fraction = i as f32 / SEGMENTS as f32;
+            let seek = move |mut timeline: Single<&mut Timeline<S>>,
+                             mut clock: ResMut<SimulationClock>| {
+                clock.playing = false;
+                let position = (fraction * timeline.steps() as f32).round() as usize;
+                timeline.seek(position);
+            };
+            p.spawn((
+                Segment(fraction),
+                BackgroundColor(DIM_GRAY.into()),
+                Node {
+                    flex_grow: 1.,
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+            ))
+            .observe(
+                move |_ev: Trigger<Pointer<Down>>,
+                      timeline: Single<&mut Timeline<S>>,
+                      clock: ResMut<SimulationClock>| seek(timeline, clock),
+            )
+            .observe(
+                move |_ev: Trigger<Pointer<DragEnter>>,
+                      timeline: Single<&mut Timeline<S>>,
+                      clock: ResMut<SimulationClock>| seek(timeline, clock),
+            );
+        }
+    });
+}
+
+/// Fills in the scrubber up to the timeline's position.
+pub fn show_progress<S: Replay>(
+    timeline: Single<&Timeline<S>>,
+    mut segments: Query<(&Segment, &mut BackgroundColor)>,
+) {
+    let progress = timeline.position() as f32 / timeline.steps().max(1) as f32;
+    for (segment, mut color) in &mut segments {
+        color.0 = if segment.0 < progress {
+            GOLD.into()
+        } else {
+            DIM_GRAY.into()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// Adds up its steps, so any state shows exactly which steps led to it.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Sum(u64);
+
+    impl Replay for Sum {
+        type Step = u64;
+
+        fn apply(&mut self, step: &u64) {
+            self.0 += step;
+        }
+    }
+
+    fn sum_to(position: u64) -> Sum {
+        Sum((1..=position).sum())
+    }
+
+    #[test]
+    fn test_seek_across_checkpoints() {
+        let mut timeline = Timeline::new(Sum(0), (1..=100).collect());
+        assert_eq!(timeline.interval, 4);
+
+        // Forwards within an interval, forwards past several, backwards onto and between
+        // checkpoints, and past the end.
+        for position in [3, 4, 5, 61, 60, 59, 2, 0, 100, 97] {
+            timeline.seek(position);
+            assert_eq!(timeline.position(), position);
+            assert_eq!(
+                *timeline.current(),
+                sum_to(position as u64),
+                "at {position}"
+            );
+        }
+        timeline.seek(1000);
+        assert_eq!(timeline.position(), 100);
+        assert!(!timeline.step_forward());
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<(), TimelineError> {
+        let dir = TempDir::new("timeline");
+        let path = dir.join("day6.timeline");
+        let timeline = Timeline::new(Sum(5), (1..=10).collect());
+        timeline.write(6, &path)?;
+
+        let mut loaded = Timeline::<Sum>::read(6, path.clone())?;
+        assert_eq!(loaded.steps(), 10);
+        assert_eq!(*loaded.current(), Sum(5));
+        loaded.seek(10);
+        assert_eq!(*loaded.current(), Sum(60));
+
+        assert!(matches!(
+            Timeline::<Sum>::read(7, path),
+            Err(TimelineError::WrongDay { found: 6, .. })
+        ));
+        Ok(())
+    }
+}
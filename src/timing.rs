@@ -0,0 +1,106 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+
+use crate::tasks::Solve;
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<StageTimed>()
+        .init_resource::<StageTimer>()
+        .add_systems(Update, show_timings);
+}
+
+/// The timed parts of each day's pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    Process,
+    SolveA,
+    SolveB,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Process => "process",
+            Self::SolveA => "solve_a",
+            Self::SolveB => "solve_b",
+        })
+    }
+}
+
+/// Sent after each stage of a day has run.
+#[derive(Event, Clone, Debug)]
+pub struct StageTimed {
+    pub day: u8,
+    pub stage: Stage,
+    pub elapsed: Duration,
+}
+
+/// When the currently running stage of a day began.
+#[derive(Resource, Debug)]
+pub struct StageTimer(Instant);
+
+impl Default for StageTimer {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+
+impl StageTimer {
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Restarts the [`StageTimer`]. Runs before each stage of a day.
+pub fn start_stage(mut timer: ResMut<StageTimer>) {
+    timer.0 = Instant::now();
+}
+
+/// Builds a system that sends a [`StageTimed`] for a stage that has just finished. Stages handed
+/// to a background [`Solve`] are timed once it finishes instead.
+pub fn finish_stage(
+    day: u8,
+    stage: Stage,
+) -> impl FnMut(Res<StageTimer>, EventWriter<StageTimed>, Query<&Solve>) {
+    move |timer, mut timed, solves| {
+        if solves.iter().any(|solve| solve.stage() == (day, stage)) {
+            return;
+        }
+        timed.send(StageTimed {
+            day,
+            stage,
+            elapsed: timer.elapsed(),
+        });
+    }
+}
+
+/// The node under each day's root that lists how long each stage took.
+#[derive(Component, Debug)]
+pub struct TimingPanel(pub u8);
+
+fn show_timings(
+    mut timed: EventReader<StageTimed>,
+    mut commands: Commands,
+    panels: Query<(Entity, &TimingPanel)>,
+) {
+    for stage in timed.read() {
+        for (entity, panel) in &panels {
+            if panel.0 != stage.day {
+                continue;
+            }
+            commands.entity(entity).with_children(|p| {
+                p.spawn((
+                    Text::new(format!("{}: {:.2?}", stage.stage, stage.elapsed)),
+                    TextFont {
+                        font_size: 12.,
+                        ..default()
+                    },
+                ));
+            });
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::{
+    color::palettes::css::{DIM_GRAY, GREEN},
+    ecs::system::IntoObserverSystem,
+    prelude::*,
+};
+
+/// Spawns a button labelled `label`, which runs `on_click` when clicked. `marker` goes on the
+/// label's text, for buttons whose label changes. Returns the button, e.g. for giving it a
+/// different [`Node`].
+pub fn spawn_button<'a, M>(
+    p: &'a mut ChildBuilder,
+    label: &str,
+    marker: impl Bundle,
+    on_click: impl IntoObserverSystem<Pointer<Click>, (), M>,
+) -> EntityCommands<'a> {
+    let mut button = p.spawn((
+        Name::new(format!("{label} Button")),
+        Button,
+        BackgroundColor(DIM_GRAY.into()),
+        BorderColor(GREEN.into()),
+        Node {
+            border: UiRect::all(Val::Px(2.)),
+            padding: UiRect::axes(Val::Px(10.), Val::Px(5.)),
+            ..default()
+        },
+    ));
+    button
+        .with_children(|p| {
+            p.spawn((
+                marker,
+                Text::new(label),
+                TextFont {
+                    font_size: 14.,
+                    ..default()
+                },
+            ));
+        })
+        .observe(on_click);
+    button
+}
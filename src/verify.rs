@@ -0,0 +1,91 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_asset_loader::prelude::*;
+
+use crate::{
+    answer::{Part, PuzzleAnswer},
+    expected_answers_asset::{ExpectedAnswersAsset, ExpectedAnswersAssetLoader},
+    loading::InputSource,
+    AoCState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.init_asset::<ExpectedAnswersAsset>()
+        .init_asset_loader::<ExpectedAnswersAssetLoader>()
+        .init_resource::<Verdicts>()
+        .configure_loading_state(
+            LoadingStateConfig::new(AoCState::Loading).load_collection::<ExpectedAnswers>(),
+        )
+        .add_systems(
+            Update,
+            check_answers.run_if(resource_exists::<ExpectedAnswers>),
+        );
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct ExpectedAnswers {
+    #[asset(path = "answers.ron")]
+    pub real: Handle<ExpectedAnswersAsset>,
+    #[asset(path = "sample/answers.ron")]
+    pub sample: Handle<ExpectedAnswersAsset>,
+}
+
+impl ExpectedAnswers {
+    pub const fn get(&self, source: InputSource) -> &Handle<ExpectedAnswersAsset> {
+        match source {
+            InputSource::Real => &self.real,
+            InputSource::Sample => &self.sample,
+        }
+    }
+}
+
+/// How a computed answer compares to the accepted one in `answers.ron` (or `sample/answers.ron`
+/// when solving the examples).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// Either the part hasn't been solved yet, or there's no accepted answer to compare with.
+    #[default]
+    Unknown,
+}
+
+/// The verdict for the latest answer to each day and part.
+#[derive(Resource, Default, Debug)]
+pub struct Verdicts(HashMap<(u8, Part), Verdict>);
+
+impl Verdicts {
+    pub fn get(&self, day: u8, part: Part) -> Verdict {
+        self.0.get(&(day, part)).copied().unwrap_or_default()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+fn check_answers(
+    mut answers: EventReader<PuzzleAnswer>,
+    expected: Res<ExpectedAnswers>,
+    expected_assets: Res<Assets<ExpectedAnswersAsset>>,
+    source: Res<InputSource>,
+    mut verdicts: ResMut<Verdicts>,
+) {
+    let Some(expected) = expected_assets.get(expected.get(*source)) else {
+        return;
+    };
+
+    for answer in answers.read() {
+        let verdict = match expected.get(answer.day, answer.part) {
+            Some(value) if value == answer.value => Verdict::Pass,
+            Some(value) => {
+                warn!(
+                    "Day {} part {:?}: got {}, expected {}",
+                    answer.day, answer.part, answer.value, value
+                );
+                Verdict::Fail
+            }
+            None => Verdict::Unknown,
+        };
+        verdicts.0.insert((answer.day, answer.part), verdict);
+    }
+}